@@ -0,0 +1,84 @@
+use std::fmt::{self, Display};
+
+use plop_tui::app::actions;
+use plop_tui::inputs::key::Key;
+use serde::Deserialize;
+
+/// We define all available action
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Suspend,
+    Sleep,
+    Cancel,
+    IncrementDelay,
+    DecrementDelay,
+    SetDelay,
+    ToggleErrors,
+    ShowSleeps,
+    Previous,
+    Next,
+    Open,
+    EditNote,
+}
+
+impl actions::Action for Action {
+    fn all() -> &'static [Self] {
+        &[
+            Action::Quit,
+            Action::Suspend,
+            Action::Sleep,
+            Action::Cancel,
+            Action::IncrementDelay,
+            Action::DecrementDelay,
+            Action::SetDelay,
+            Action::ToggleErrors,
+            Action::ShowSleeps,
+            Action::Previous,
+            Action::Next,
+            Action::Open,
+            Action::EditNote,
+        ]
+    }
+
+    fn default_keys(&self) -> &[Key] {
+        match self {
+            Action::Quit => &[Key::Ctrl('c'), Key::Char('q')],
+            Action::Suspend => &[Key::Ctrl('z')],
+            Action::Sleep => &[Key::Char('s')],
+            Action::Cancel => &[Key::Char('c')],
+            Action::IncrementDelay => &[Key::Char('+')],
+            Action::DecrementDelay => &[Key::Char('-')],
+            Action::SetDelay => &[Key::Char('d')],
+            Action::ToggleErrors => &[Key::Char('e')],
+            Action::ShowSleeps => &[Key::Char('h')],
+            Action::Previous => &[Key::Up, Key::Char('k')],
+            Action::Next => &[Key::Down, Key::Char('j')],
+            Action::Open => &[Key::Enter],
+            Action::EditNote => &[Key::Char('i')],
+        }
+    }
+}
+
+/// Could display a user friendly short description of action
+impl Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            Action::Quit => "Quit",
+            Action::Suspend => "Suspend",
+            Action::Sleep => "Sleep",
+            Action::Cancel => "Cancel last task",
+            Action::IncrementDelay => "Increment delay",
+            Action::DecrementDelay => "Decrement delay",
+            Action::SetDelay => "Set delay",
+            Action::ToggleErrors => "Error history",
+            Action::ShowSleeps => "Sleep history",
+            Action::Previous => "Previous",
+            Action::Next => "Next",
+            Action::Open => "Open",
+            Action::EditNote => "Edit note",
+        };
+        write!(f, "{}", str)
+    }
+}
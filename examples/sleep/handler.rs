@@ -0,0 +1,154 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use log::info;
+use plop_tui::io::policy::Policy;
+use plop_tui::io::progress::ProgressReporter;
+use plop_tui::io::IoHandler;
+use tokio::time::Instant;
+
+/// Delay between two progress reports of a sleep
+const SLEEP_TICK: Duration = Duration::from_millis(100);
+/// Delay allowed after the end of a sleep, before it times out
+const SLEEP_MARGIN: Duration = Duration::from_secs(1);
+
+// For this dummy application we only need two IO event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoEvent {
+    Initialize,      // Launch to initialize the application
+    Sleep(Duration), // Just take a little break
+}
+
+impl Display for IoEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoEvent::Initialize => write!(f, "Initialize"),
+            IoEvent::Sleep(duration) => write!(f, "Sleep {:?}", duration),
+        }
+    }
+}
+
+/// The successful outcome of an IO event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoOutcome {
+    Initialized,     // The application is ready
+    Slept(Duration), // The break is over
+}
+
+/// The failure of an IO event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SleepError {
+    /// Cannot sleep for this duration
+    InvalidDuration(Duration),
+}
+
+impl Display for SleepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SleepError::InvalidDuration(duration) => write!(f, "Cannot sleep for {:?}", duration),
+        }
+    }
+}
+
+impl Error for SleepError {}
+
+/// In the IO thread, we handle IO event without blocking the UI thread
+#[derive(Debug, Default)]
+pub struct SleepHandler;
+
+type Reporter = ProgressReporter<IoOutcome, SleepError>;
+
+impl IoHandler for SleepHandler {
+    type Event = IoEvent;
+    type Outcome = IoOutcome;
+    type Error = SleepError;
+
+    async fn handle(&self, event: &IoEvent, reporter: &Reporter) -> Result<IoOutcome, SleepError> {
+        match event {
+            IoEvent::Initialize => do_initialize(reporter).await,
+            IoEvent::Sleep(duration) => do_sleep(reporter, *duration).await,
+        }
+    }
+
+    fn policy(&self, event: &IoEvent) -> Policy {
+        match event {
+            IoEvent::Initialize => Policy::default().timeout(Duration::from_secs(5)).retries(3),
+            // A sleep only times out if it's late
+            IoEvent::Sleep(duration) => Policy::default()
+                .timeout(duration.saturating_add(SLEEP_MARGIN))
+                .retries(2),
+        }
+    }
+}
+
+/// We use dummy implementation here, just wait 1s
+async fn do_initialize(_reporter: &Reporter) -> Result<IoOutcome, SleepError> {
+    info!("🚀 Initialize the application");
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    info!("👍 Application initialized");
+
+    // we could update the app state
+    Ok(IoOutcome::Initialized)
+}
+
+/// Just take a little break, reporting the progress at each tick
+async fn do_sleep(reporter: &Reporter, duration: Duration) -> Result<IoOutcome, SleepError> {
+    if duration.is_zero() {
+        return Err(SleepError::InvalidDuration(duration));
+    }
+    info!("😴 Go sleeping for {:?}...", duration);
+    let started = Instant::now();
+    let mut ticks = tokio::time::interval(SLEEP_TICK);
+    loop {
+        ticks.tick().await;
+        let slept = started.elapsed().min(duration);
+        if slept >= duration {
+            break;
+        }
+        let ratio = slept.as_secs_f64() / duration.as_secs_f64();
+        let message = format!("Slept {:.1}s of {:?}", slept.as_secs_f64(), duration);
+        reporter.report(ratio, message).await;
+    }
+    info!("⏰ Wake up !");
+
+    // Notify the app for having slept
+    Ok(IoOutcome::Slept(duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use plop_tui::io::{IoMessage, TaskId};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn should_display_sub_second_sleep() {
+        let event = IoEvent::Sleep(Duration::from_millis(500));
+        assert_eq!(event.to_string(), "Sleep 500ms");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_sleep_within_its_timeout() {
+        let (tx, mut rx) = mpsc::channel(200);
+        let reporter = Reporter::new(TaskId(1), tx);
+        let duration = Duration::from_secs(10);
+        let event = IoEvent::Sleep(duration);
+        let timeout = SleepHandler.policy(&event).timeout.unwrap();
+
+        let started = Instant::now();
+        let result = tokio::time::timeout(timeout, SleepHandler.handle(&event, &reporter)).await;
+        assert_eq!(result, Ok(Ok(IoOutcome::Slept(duration))));
+        assert!(started.elapsed() >= duration);
+
+        drop(reporter);
+        let mut last = None;
+        while let Some(IoMessage::Progress(_, progress)) = rx.recv().await {
+            last = Some(progress);
+        }
+        let last = last.unwrap();
+        assert!(last.ratio > 0.9 && last.ratio < 1.0, "{:?}", last);
+        assert!(last.eta.unwrap() <= SLEEP_TICK, "{:?}", last);
+    }
+}
@@ -0,0 +1,103 @@
+use eyre::{bail, Result};
+use log::LevelFilter;
+use plop_tui::app::keymap::Keymap;
+use plop_tui::app::App;
+use plop_tui::io::handler::IoAsyncHandler;
+use plop_tui::terminal::TerminalBuilder;
+use plop_tui::{start_ui, UiOptions};
+
+use crate::handler::SleepHandler;
+use crate::model::SleepModel;
+
+mod actions;
+mod handler;
+mod model;
+mod state;
+
+/// Height of the UI when rendered below the prompt
+const INLINE_HEIGHT: u16 = 16;
+/// Maximum number of IO tasks running at the same time
+const MAX_IO_TASKS: usize = 2;
+
+const USAGE: &str = "Usage: sleep [--inline] [keymap.toml]";
+
+/// The command line arguments
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Args {
+    help: bool,
+    inline: bool,
+    keymap: Option<String>,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args> {
+    let mut parsed = Args::default();
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "--inline" => parsed.inline = true,
+            option if option.starts_with('-') => bail!("Unknown option {}\n{}", option, USAGE),
+            // An optional keymap file could override the default keys
+            path if parsed.keymap.is_none() => parsed.keymap = Some(path.to_string()),
+            path => bail!("Unexpected argument {}, only one keymap\n{}", path, USAGE),
+        }
+    }
+    Ok(parsed)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let mut terminal_builder = TerminalBuilder::default();
+    if args.inline {
+        terminal_builder = terminal_builder.inline(INLINE_HEIGHT);
+    }
+    // The conflicting keys of a keymap file are reported before starting the UI
+    let keymap = match &args.keymap {
+        Some(path) => Keymap::load(path, &SleepModel::action_sets())?,
+        None => Keymap::default(),
+    };
+
+    let (sync_io_tx, sync_io_rx) = tokio::sync::mpsc::channel(100);
+    // The IO results are sent back to the UI loop
+    let (io_message_tx, io_message_rx) = tokio::sync::mpsc::channel(100);
+
+    // Quitting while sleeping is confirmed
+    let app = App::new(SleepModel::default(), sync_io_tx, keymap).confirm_exit(true);
+
+    // Configure log
+    tui_logger::init_logger(LevelFilter::Debug).unwrap();
+    tui_logger::set_default_level(log::LevelFilter::Debug);
+
+    // Handle IO in a specifc thread, each event is run as a concurrent task
+    let handler = IoAsyncHandler::new(SleepHandler, io_message_tx, MAX_IO_TASKS);
+    tokio::spawn(handler.run(sync_io_rx));
+
+    let options = UiOptions::default().terminal(terminal_builder);
+    start_ui(app, io_message_rx, options).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        parse_args(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn should_parse_args() {
+        let args = parse(&["--inline", "keymap.toml"]).unwrap();
+        assert!(args.inline);
+        assert_eq!(args.keymap.as_deref(), Some("keymap.toml"));
+        assert!(parse(&["--help"]).unwrap().help);
+
+        assert!(parse(&["--inlne"]).is_err());
+        assert!(parse(&["a.toml", "b.toml"]).is_err());
+    }
+}
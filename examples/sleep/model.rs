@@ -0,0 +1,620 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use plop_tui::app::command::{Command, CommandSpec};
+use plop_tui::app::input::TextInput;
+use plop_tui::app::modal::Modal;
+use plop_tui::app::model::{Model, ModelCmd, ModelMsg, Msg, Panels};
+use plop_tui::app::notifications::Severity;
+use plop_tui::inputs::key::Key;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::symbols::line;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, LineGauge, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+use crate::actions::Action;
+use crate::handler::{IoEvent, IoOutcome, SleepError};
+use crate::state::{AppState, MAX_DELAY, MIN_DELAY};
+
+type Cmd = ModelCmd<SleepModel>;
+
+/// The commands of the command line, e.g. `:sleep 7s`
+const COMMANDS: [CommandSpec; 7] = [
+    CommandSpec::new("sleep", "[duration]", "Sleep, e.g. 7s or 500ms"),
+    CommandSpec::new("delay", "<seconds>", "Set the sleep delay"),
+    CommandSpec::new("cancel", "", "Cancel the last task"),
+    CommandSpec::new("history", "", "Show the sleep history"),
+    CommandSpec::new("errors", "", "Show the error history"),
+    CommandSpec::new("suspend", "", "Suspend"),
+    CommandSpec::new("quit", "", "Quit"),
+];
+
+/// The longest sleep of the `:sleep` command
+const MAX_SLEEP: Duration = Duration::from_secs(60 * 60);
+
+/// The actions of the initialized main screen
+const MAIN_ACTIONS: [Action; 9] = [
+    Action::Quit,
+    Action::Suspend,
+    Action::Sleep,
+    Action::Cancel,
+    Action::IncrementDelay,
+    Action::DecrementDelay,
+    Action::SetDelay,
+    Action::ToggleErrors,
+    Action::ShowSleeps,
+];
+/// The actions of the sleep history
+const SLEEPS_ACTIONS: [Action; 5] = [
+    Action::Quit,
+    Action::Previous,
+    Action::Next,
+    Action::Open,
+    Action::ToggleErrors,
+];
+/// The actions of a sleep detail
+const SLEEP_ACTIONS: [Action; 4] = [
+    Action::Quit,
+    Action::Sleep,
+    Action::EditNote,
+    Action::ToggleErrors,
+];
+
+/// The screens of the demo: the sleep history is a list, with the detail of each sleep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Screen {
+    #[default]
+    Main,
+    Sleeps,
+    Sleep(usize),
+}
+
+/// The sleep demo, sleeping in IO tasks
+#[derive(Default)]
+pub struct SleepModel {
+    state: AppState,
+    /// The durations of the sleeps
+    history: Vec<Duration>,
+    /// The selected sleep of the history
+    selected: usize,
+    /// The notes of the sleeps, by index
+    notes: HashMap<usize, String>,
+    /// The note being edited
+    editing: Option<TextInput>,
+}
+
+impl SleepModel {
+    /// The actions available together, on each screen, their keys should not conflict
+    pub fn action_sets() -> Vec<Vec<Action>> {
+        vec![
+            MAIN_ACTIONS.to_vec(),
+            SLEEPS_ACTIONS.to_vec(),
+            SLEEP_ACTIONS.to_vec(),
+        ]
+    }
+}
+
+impl Model for SleepModel {
+    type Action = Action;
+    type Event = IoEvent;
+    type Outcome = IoOutcome;
+    type Error = SleepError;
+    type Screen = Screen;
+
+    fn title(&self, screen: &Screen) -> String {
+        match screen {
+            Screen::Main => String::from("Plop with TUI"),
+            Screen::Sleeps => String::from("Plop with TUI › Sleeps"),
+            Screen::Sleep(index) => format!("Plop with TUI › Sleeps › #{}", index + 1),
+        }
+    }
+
+    fn actions(&self, screen: &Screen) -> Vec<Action> {
+        match screen {
+            Screen::Main if self.state.is_initialized() => MAIN_ACTIONS.to_vec(),
+            Screen::Main => vec![Action::Quit, Action::Suspend, Action::ToggleErrors],
+            Screen::Sleeps => SLEEPS_ACTIONS.to_vec(),
+            Screen::Sleep(_) => SLEEP_ACTIONS.to_vec(),
+        }
+    }
+
+    fn commands(&self, _screen: &Screen) -> Vec<CommandSpec> {
+        if self.state.is_initialized() {
+            COMMANDS.to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn is_focused(&self, screen: &Screen) -> bool {
+        matches!(screen, Screen::Sleep(_)) && self.editing.is_some()
+    }
+
+    /// The history and the detail of a sleep use the space of the tasks
+    fn panels(&self, screen: &Screen) -> Panels {
+        let tasks = matches!(screen, Screen::Main);
+        Panels {
+            tasks,
+            ..Panels::default()
+        }
+    }
+
+    fn update(mut self, screen: &Screen, msg: ModelMsg<Self>) -> (Self, Cmd) {
+        let cmd = match msg {
+            // Here we assume the the first load is a long task
+            Msg::Init => Cmd::Dispatch(IoEvent::Initialize),
+            Msg::Action(action) => self.on_action(screen, action),
+            Msg::Input(Action::SetDelay, input) => match parse_delay(input.trim()) {
+                Ok(delay) => {
+                    self.state.set_delay(delay);
+                    Cmd::None
+                }
+                Err(message) => Cmd::Modal(Modal::alert("Invalid delay", message)),
+            },
+            Msg::Input(..) => Cmd::None,
+            Msg::Key(key) => {
+                self.on_key(screen, key);
+                Cmd::None
+            }
+            Msg::Paste(text) => {
+                if let Some(input) = &mut self.editing {
+                    input.paste(&text);
+                }
+                Cmd::None
+            }
+            Msg::Command(command) => match self.on_command(screen, command) {
+                Ok(cmd) => cmd,
+                Err(err) => Cmd::notify(Severity::Error, err),
+            },
+            // The view does not change on tick, no frame is drawn while idle
+            Msg::Tick => Cmd::None,
+            Msg::Outcome(_, Ok(IoOutcome::Initialized)) => {
+                self.state = AppState::initialized();
+                Cmd::None
+            }
+            Msg::Outcome(_, Ok(IoOutcome::Slept(duration))) => {
+                self.state.incr_sleep();
+                self.history.push(duration);
+                Cmd::None
+            }
+            // The failures are already notified
+            Msg::Outcome(_, Err(_)) => Cmd::None,
+        };
+        (self, cmd)
+    }
+
+    fn view<B: Backend>(&self, screen: &Screen, rect: &mut Frame<B>, area: Rect) {
+        match screen {
+            Screen::Main => self.view_main(rect, area),
+            Screen::Sleeps => {
+                let mut state = ListState::default();
+                state.select(Some(self.selected));
+                let sleeps = draw_sleeps(&self.history);
+                rect.render_stateful_widget(sleeps, area, &mut state);
+            }
+            Screen::Sleep(index) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+                    .split(area);
+                let sleep = draw_sleep(*index, self.history.get(*index));
+                rect.render_widget(sleep, chunks[0]);
+
+                // The note is edited in place
+                match &self.editing {
+                    Some(input) => {
+                        let block = Block::default()
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(Color::LightCyan))
+                            .title("Note, <Esc> to save");
+                        input.render(rect, chunks[1], Some(block), true);
+                    }
+                    None => {
+                        let note = self.notes.get(index).cloned().unwrap_or_default();
+                        let note = Paragraph::new(note)
+                            .block(Block::default().borders(Borders::ALL).title("Note"));
+                        rect.render_widget(note, chunks[1]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SleepModel {
+    fn on_action(&mut self, screen: &Screen, action: Action) -> Cmd {
+        match (screen, action) {
+            (_, Action::Quit) => Cmd::Exit,
+            (_, Action::Suspend) => Cmd::Suspend,
+            // Sleep is an I/O action, we dispatch on the IO channel that's run on another thread
+            (Screen::Sleep(index), Action::Sleep) => match self.history.get(*index) {
+                Some(duration) => Cmd::Dispatch(IoEvent::Sleep(*duration)),
+                None => Cmd::None,
+            },
+            (_, Action::Sleep) => match self.state.duration() {
+                Some(duration) => Cmd::Dispatch(IoEvent::Sleep(*duration)),
+                None => Cmd::None,
+            },
+            (_, Action::Cancel) => Cmd::CancelLastTask,
+            // IncrementDelay and DecrementDelay is handled in the UI thread
+            (_, Action::IncrementDelay) => {
+                self.state.increment_delay();
+                Cmd::None
+            }
+            // Note, that we clamp the duration, so we stay >= 0
+            (_, Action::DecrementDelay) => {
+                self.state.decrement_delay();
+                Cmd::None
+            }
+            (_, Action::SetDelay) => {
+                let prompt = Modal::prompt("Delay", "Sleep delay in seconds, 1 to 10", action);
+                Cmd::Modal(prompt)
+            }
+            // The note being edited is kept, its input gets the keys back
+            (Screen::Sleep(index), Action::EditNote) => {
+                if self.editing.is_none() {
+                    let mut input = TextInput::multi_line();
+                    input.set_value(self.notes.get(index).map_or("", String::as_str));
+                    self.editing = Some(input);
+                }
+                Cmd::Focus
+            }
+            (_, Action::EditNote) => Cmd::None,
+            (_, Action::ToggleErrors) => Cmd::ToggleErrors,
+            (_, Action::ShowSleeps) => Cmd::Push(Screen::Sleeps),
+            (_, Action::Previous) => {
+                self.selected = self.selected.saturating_sub(1);
+                Cmd::None
+            }
+            (_, Action::Next) => {
+                self.selected = (self.selected + 1).min(self.history.len().saturating_sub(1));
+                Cmd::None
+            }
+            (_, Action::Open) if self.selected < self.history.len() => {
+                Cmd::Push(Screen::Sleep(self.selected))
+            }
+            (_, Action::Open) => Cmd::None,
+        }
+    }
+
+    /// Edit the note, until `<Esc>` is pressed
+    fn on_key(&mut self, screen: &Screen, key: Key) {
+        match (screen, key, &mut self.editing) {
+            (Screen::Sleep(index), Key::Esc, Some(input)) => {
+                self.notes.insert(*index, input.value());
+                self.editing = None;
+            }
+            (_, key, Some(input)) => {
+                input.on_key(key);
+            }
+            _ => {}
+        }
+    }
+
+    /// Map the commands onto the actions, with their arguments
+    fn on_command(&mut self, screen: &Screen, command: Command) -> Result<Cmd, String> {
+        let arg = command.args.first();
+        let cmd = match (command.name, arg) {
+            ("sleep", Some(arg)) => Cmd::Dispatch(IoEvent::Sleep(parse_duration(arg)?)),
+            ("delay", Some(arg)) => {
+                self.state.set_delay(parse_delay(arg)?);
+                Cmd::None
+            }
+            ("delay", None) => return Err(String::from("Usage: :delay <seconds>")),
+            ("sleep", None) => self.on_action(screen, Action::Sleep),
+            ("cancel", _) => self.on_action(screen, Action::Cancel),
+            ("history", _) => self.on_action(screen, Action::ShowSleeps),
+            ("errors", _) => self.on_action(screen, Action::ToggleErrors),
+            ("suspend", _) => self.on_action(screen, Action::Suspend),
+            ("quit", _) => self.on_action(screen, Action::Quit),
+            (name, _) => return Err(format!("Unknown command: {}", name)),
+        };
+        Ok(cmd)
+    }
+
+    fn view_main<B: Backend>(&self, rect: &mut Frame<B>, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)].as_ref())
+            .split(area);
+
+        let body = draw_body(&self.state);
+        rect.render_widget(body, chunks[0]);
+
+        // Duration LineGauge
+        if let Some(duration) = self.state.duration() {
+            let duration_block = draw_duration(duration);
+            rect.render_widget(duration_block, chunks[1]);
+        }
+    }
+}
+
+/// Parse a duration, e.g. `7`, `7s`, `1.5s` or `500ms`, up to [`MAX_SLEEP`]
+fn parse_duration(arg: &str) -> Result<Duration, String> {
+    let invalid = || format!("{:?} is not a duration, e.g. 7s or 500ms", arg);
+    let (value, unit) = match arg.find(|c: char| c.is_alphabetic()) {
+        Some(index) => arg.split_at(index),
+        None => (arg, "s"),
+    };
+    let value: f64 = value.parse().map_err(|_| invalid())?;
+    let secs = match unit {
+        "ms" => value / 1000.0,
+        "s" => value,
+        "m" => value * 60.0,
+        _ => return Err(invalid()),
+    };
+    match Duration::try_from_secs_f64(secs) {
+        Ok(duration) if duration > MAX_SLEEP => Err(format!(
+            "{:?} is too long, the longest sleep is {:?}",
+            arg, MAX_SLEEP
+        )),
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse a sleep delay, a number of seconds between 1 and 10
+fn parse_delay(arg: &str) -> Result<Duration, String> {
+    match arg.parse() {
+        Ok(secs) if (MIN_DELAY..=MAX_DELAY).contains(&secs) => Ok(Duration::from_secs(secs)),
+        _ => Err(format!(
+            "{:?} is not a delay, from {} to {} seconds",
+            arg, MIN_DELAY, MAX_DELAY
+        )),
+    }
+}
+
+fn draw_sleeps(history: &[Duration]) -> List<'_> {
+    let items: Vec<_> = history
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| ListItem::new(format!("#{} slept {:?}", index + 1, duration)))
+        .collect();
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sleep history"),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ")
+}
+
+fn draw_sleep<'a>(index: usize, duration: Option<&Duration>) -> Paragraph<'a> {
+    let text = match duration {
+        Some(duration) => format!("Slept {:?}", duration),
+        None => String::from("Unknown sleep"),
+    };
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::LightCyan))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Sleep #{}", index + 1)),
+        )
+}
+
+fn draw_body<'a>(state: &AppState) -> Paragraph<'a> {
+    let initialized_text = if state.is_initialized() {
+        "Initialized"
+    } else {
+        "Not Initialized !"
+    };
+    let sleep_text = if let Some(sleeps) = state.count_sleep() {
+        format!("Sleep count: {}", sleeps)
+    } else {
+        String::default()
+    };
+    Paragraph::new(vec![
+        Spans::from(Span::raw(initialized_text)),
+        Spans::from(Span::raw(sleep_text)),
+    ])
+    .style(Style::default().fg(Color::LightCyan))
+    .alignment(Alignment::Left)
+    .block(
+        Block::default()
+            // .title("Body")
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn draw_duration(duration: &Duration) -> LineGauge<'_> {
+    let sec = duration.as_secs();
+    let label = format!("{}s", sec);
+    let ratio = sec as f64 / 10.0;
+    LineGauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sleep duration"),
+        )
+        .gauge_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .bg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .line_set(line::THICK)
+        .label(label)
+        .ratio(ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use plop_tui::app::actions::Actions;
+    use plop_tui::app::keymap::Keymap;
+    use plop_tui::io::TaskId;
+
+    use super::*;
+
+    fn initialized() -> SleepModel {
+        let (model, _) = SleepModel::default().update(&Screen::Main, Msg::Init);
+        let (model, _) = model.update(
+            &Screen::Main,
+            Msg::Outcome(TaskId(1), Ok(IoOutcome::Initialized)),
+        );
+        model
+    }
+
+    #[test]
+    fn should_load_documented_keymap() {
+        // The keymap of the README, `<Up>` and `<Down>` also move in the sleep history
+        let keymap = r#"
+            sequence_timeout = 500
+            quit = ["<Ctrl+c>", "<Esc>"]
+            sleep = ["<Space>", "<Ctrl+x> <s>"]
+            increment_delay = ["<Up>"]
+            decrement_delay = ["<Down>"]
+        "#;
+        let keymap: Keymap<Action> = keymap.parse().unwrap();
+        let model = initialized();
+        for screen in [Screen::Main, Screen::Sleeps, Screen::Sleep(0)] {
+            let actions = Actions::new(model.actions(&screen), &keymap);
+            assert!(actions.is_ok(), "{:?}: {:?}", screen, actions.err());
+        }
+        for actions in SleepModel::action_sets() {
+            assert!(keymap.check(&actions).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_initialize() {
+        let (model, cmd) = SleepModel::default().update(&Screen::Main, Msg::Init);
+        assert_eq!(cmd, Cmd::Dispatch(IoEvent::Initialize));
+        assert!(!model.state.is_initialized());
+        assert!(!model.actions(&Screen::Main).contains(&Action::Sleep));
+
+        let model = initialized();
+        assert!(model.state.is_initialized());
+        assert!(model.actions(&Screen::Main).contains(&Action::Sleep));
+    }
+
+    #[test]
+    fn should_not_redraw_on_tick() {
+        let (_, cmd) = initialized().update(&Screen::Main, Msg::Tick);
+        assert_eq!(cmd, Cmd::None);
+    }
+
+    #[test]
+    fn should_sleep_with_delay() {
+        let (model, _) = initialized().update(&Screen::Main, Msg::Action(Action::IncrementDelay));
+        let (model, cmd) = model.update(&Screen::Main, Msg::Action(Action::Sleep));
+        let duration = Duration::from_secs(2);
+        assert_eq!(cmd, Cmd::Dispatch(IoEvent::Sleep(duration)));
+
+        let (model, _) = model.update(
+            &Screen::Main,
+            Msg::Outcome(TaskId(2), Ok(IoOutcome::Slept(duration))),
+        );
+        assert_eq!(model.state.count_sleep(), Some(1));
+    }
+
+    #[test]
+    fn should_set_delay_from_prompt() {
+        let (model, cmd) = initialized().update(&Screen::Main, Msg::Action(Action::SetDelay));
+        assert!(matches!(cmd, Cmd::Modal(_)));
+
+        let input = Msg::Input(Action::SetDelay, String::from("7"));
+        let (model, cmd) = model.update(&Screen::Main, input);
+        assert_eq!(cmd, Cmd::None);
+        assert_eq!(model.state.duration(), Some(&Duration::from_secs(7)));
+
+        let input = Msg::Input(Action::SetDelay, String::from("seven"));
+        let (_, cmd) = model.update(&Screen::Main, input);
+        assert!(matches!(cmd, Cmd::Modal(_)));
+    }
+
+    #[test]
+    fn should_parse_duration() {
+        assert_eq!(parse_duration("7"), Ok(Duration::from_secs(7)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert!(parse_duration("7h").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("100000000000000000000").is_err());
+        assert_eq!(parse_duration("60m"), Ok(MAX_SLEEP));
+        assert!(parse_duration("61m").is_err());
+        assert!(parse_duration("1e12s").is_err());
+    }
+
+    #[test]
+    fn should_parse_delay() {
+        assert_eq!(parse_delay("10"), Ok(Duration::from_secs(10)));
+        assert!(parse_delay("0").is_err());
+        assert!(parse_delay("30").is_err());
+        assert!(parse_delay("seven").is_err());
+    }
+
+    #[test]
+    fn should_run_commands() {
+        let command = |name, args: &[&str]| {
+            let args = args.iter().map(ToString::to_string).collect();
+            Msg::Command(Command { name, args })
+        };
+        let (model, cmd) = initialized().update(&Screen::Main, command("delay", &["3"]));
+        assert_eq!(cmd, Cmd::None);
+        assert_eq!(model.state.duration(), Some(&Duration::from_secs(3)));
+
+        let (model, cmd) = model.update(&Screen::Main, command("sleep", &["7s"]));
+        let duration = Duration::from_secs(7);
+        assert_eq!(cmd, Cmd::Dispatch(IoEvent::Sleep(duration)));
+
+        let (model, cmd) = model.update(&Screen::Main, command("delay", &[]));
+        assert!(matches!(cmd, Cmd::Notify(Severity::Error, _)));
+
+        let (model, cmd) = model.update(&Screen::Main, command("delay", &["30"]));
+        assert!(matches!(cmd, Cmd::Notify(Severity::Error, _)));
+        assert_eq!(model.state.duration(), Some(&Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn should_open_a_sleep_of_the_history() {
+        let mut model = initialized();
+        for secs in 1..=3 {
+            let outcome = Ok(IoOutcome::Slept(Duration::from_secs(secs)));
+            (model, _) = model.update(&Screen::Main, Msg::Outcome(TaskId(secs), outcome));
+        }
+        let (model, cmd) = model.update(&Screen::Main, Msg::Action(Action::ShowSleeps));
+        assert_eq!(cmd, Cmd::Push(Screen::Sleeps));
+
+        let (model, _) = model.update(&Screen::Sleeps, Msg::Action(Action::Next));
+        let (model, cmd) = model.update(&Screen::Sleeps, Msg::Action(Action::Open));
+        assert_eq!(cmd, Cmd::Push(Screen::Sleep(1)));
+
+        // Sleep again for the duration of the opened sleep
+        let (_, cmd) = model.update(&Screen::Sleep(1), Msg::Action(Action::Sleep));
+        let duration = Duration::from_secs(2);
+        assert_eq!(cmd, Cmd::Dispatch(IoEvent::Sleep(duration)));
+    }
+
+    #[test]
+    fn should_edit_note() {
+        let screen = Screen::Sleep(0);
+        let (model, _) = initialized().update(&screen, Msg::Action(Action::EditNote));
+        assert!(model.is_focused(&screen));
+
+        let (model, _) = model.update(&screen, Msg::Paste(String::from("zz\nz")));
+        // Editing again keeps the text being edited
+        let (model, cmd) = model.update(&screen, Msg::Action(Action::EditNote));
+        assert_eq!(cmd, Cmd::Focus);
+        let keys = [Key::Char('z'), Key::Enter, Key::Char('z'), Key::Esc];
+        let model = keys.into_iter().fold(model, |model, key| {
+            let (model, cmd) = model.update(&screen, Msg::Key(key));
+            assert_eq!(cmd, Cmd::None);
+            model
+        });
+        assert!(!model.is_focused(&screen));
+        assert_eq!(model.notes.get(&0).map(String::as_str), Some("zz\nzz\nz"));
+    }
+
+    #[test]
+    fn should_not_sleep_before_initialization() {
+        let (_, cmd) = SleepModel::default().update(&Screen::Main, Msg::Action(Action::Sleep));
+        assert_eq!(cmd, Cmd::None);
+    }
+}
@@ -1,12 +1,17 @@
 use std::time::Duration;
 
-#[derive(Clone)]
+/// The shortest sleep delay, in seconds
+pub const MIN_DELAY: u64 = 1;
+/// The longest sleep delay, in seconds
+pub const MAX_DELAY: u64 = 10;
+
+#[derive(Clone, Default)]
 pub enum AppState {
+    #[default]
     Init,
     Initialized {
         duration: Duration,
         counter_sleep: u32,
-        counter_tick: u64,
     },
 }
 
@@ -14,11 +19,9 @@ impl AppState {
     pub fn initialized() -> Self {
         let duration = Duration::from_secs(1);
         let counter_sleep = 0;
-        let counter_tick = 0;
         Self::Initialized {
             duration,
             counter_sleep,
-            counter_tick,
         }
     }
 
@@ -32,12 +35,6 @@ impl AppState {
         }
     }
 
-    pub fn incr_tick(&mut self) {
-        if let Self::Initialized { counter_tick, .. } = self {
-            *counter_tick += 1;
-        }
-    }
-
     pub fn count_sleep(&self) -> Option<u32> {
         if let Self::Initialized { counter_sleep, .. } = self {
             Some(*counter_sleep)
@@ -46,14 +43,6 @@ impl AppState {
         }
     }
 
-    pub fn count_tick(&self) -> Option<u64> {
-        if let Self::Initialized { counter_tick, .. } = self {
-            Some(*counter_tick)
-        } else {
-            None
-        }
-    }
-
     pub fn duration(&self) -> Option<&Duration> {
         if let Self::Initialized { duration, .. } = self {
             Some(duration)
@@ -65,22 +54,27 @@ impl AppState {
     pub fn increment_delay(&mut self) {
         if let Self::Initialized { duration, .. } = self {
             // Set the duration, note that the duration is in 1s..10s
-            let secs = (duration.as_secs() + 1).clamp(1, 10);
+            let secs = (duration.as_secs() + 1).clamp(MIN_DELAY, MAX_DELAY);
             *duration = Duration::from_secs(secs);
         }
     }
 
-    pub fn decrement_delay(&mut self) {
+    pub fn set_delay(&mut self, delay: Duration) {
         if let Self::Initialized { duration, .. } = self {
-            // Set the duration, note that the duration is in 1s..10s
-            let secs = (duration.as_secs() - 1).clamp(1, 10);
+            // The delay typed by the user could be out of bounds
+            let secs = delay.as_secs().clamp(MIN_DELAY, MAX_DELAY);
             *duration = Duration::from_secs(secs);
         }
     }
-}
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::Init
+    pub fn decrement_delay(&mut self) {
+        if let Self::Initialized { duration, .. } = self {
+            // Set the duration, note that the duration is in 1s..10s
+            let secs = duration
+                .as_secs()
+                .saturating_sub(1)
+                .clamp(MIN_DELAY, MAX_DELAY);
+            *duration = Duration::from_secs(secs);
+        }
     }
 }
@@ -1,146 +1,160 @@
-use std::collections::HashMap;
-use std::fmt::{self, Display};
-use std::slice::Iter;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::time::Duration;
 
+use serde::de::DeserializeOwned;
+
+use super::keymap::{Keymap, KeymapError};
 use crate::inputs::key::Key;
+use crate::inputs::sequence::KeySequence;
+
+/// The actions of an application, bound to keys.
+/// The `Display` is a user friendly short description of the action,
+/// the keymap file uses the `Deserialize` names
+pub trait Action:
+    Debug + Display + Copy + Eq + Hash + DeserializeOwned + Send + Sync + 'static
+{
+    /// All available actions
+    fn all() -> &'static [Self];
 
-/// We define all available action
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Action {
-    Quit,
-    Sleep,
-    IncrementDelay,
-    DecrementDelay,
+    /// List of key associated to action by default
+    fn default_keys(&self) -> &[Key];
 }
 
-impl Action {
-    /// All available actions
-    pub fn iterator() -> Iter<'static, Action> {
-        static ACTIONS: [Action; 4] = [
-            Action::Quit,
-            Action::Sleep,
-            Action::IncrementDelay,
-            Action::DecrementDelay,
-        ];
-        ACTIONS.iter()
-    }
+/// The application should have some contextual actions.
+#[derive(Debug, Clone)]
+pub struct Actions<A: Action> {
+    actions: Vec<A>,
+    keymap: Keymap<A>,
+}
 
-    /// List of key associated to action
-    pub fn keys(&self) -> &[Key] {
-        match self {
-            Action::Quit => &[Key::Ctrl('c'), Key::Char('q')],
-            Action::Sleep => &[Key::Char('s')],
-            Action::IncrementDelay => &[Key::Char('+')],
-            Action::DecrementDelay => &[Key::Char('-')],
+impl<A: Action> Default for Actions<A> {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+            keymap: Keymap::default(),
         }
     }
 }
 
-/// Could display a user friendly short description of action
-impl Display for Action {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let str = match self {
-            Action::Quit => "Quit",
-            Action::Sleep => "Sleep",
-            Action::IncrementDelay => "Increment delay",
-            Action::DecrementDelay => "Decrement delay",
-        };
-        write!(f, "{}", str)
+impl<A: Action> Actions<A> {
+    /// Build contextual actions with their keys
+    ///
+    /// # Errors
+    ///
+    /// If two actions have same key
+    pub fn new(actions: Vec<A>, keymap: &Keymap<A>) -> Result<Self, KeymapError<A>> {
+        // Check key unicity
+        keymap.check(&actions)?;
+
+        // Ok, we can create contextual actions
+        let keymap = keymap.clone();
+        Ok(Self { actions, keymap })
     }
-}
 
-/// The application should have some contextual actions.
-#[derive(Default, Debug, Clone)]
-pub struct Actions(Vec<Action>);
-
-impl Actions {
-    /// Given a key, find the corresponding action
-    pub fn find(&self, key: Key) -> Option<&Action> {
-        Action::iterator()
-            .filter(|action| self.0.contains(action))
-            .find(|action| action.keys().contains(&key))
+    /// Build contextual actions without checking their keys,
+    /// the first action of [`Action::all`] wins on conflict
+    pub(crate) fn unchecked(actions: Vec<A>, keymap: Keymap<A>) -> Self {
+        Self { actions, keymap }
+    }
+
+    /// Given a key sequence, find the corresponding action
+    pub fn find(&self, keys: &[Key]) -> Option<&A> {
+        A::all()
+            .iter()
+            .filter(|action| self.actions.contains(action))
+            .find(|action| self.keys(action).iter().any(|seq| seq.keys() == keys))
+    }
+
+    /// If the key sequence is the beginning of a longer sequence of an action
+    pub fn is_continued_by(&self, keys: &[Key]) -> bool {
+        self.actions
+            .iter()
+            .flat_map(|action| self.keys(action))
+            .any(|seq| seq.is_continued_by(keys))
+    }
+
+    /// List of key sequences associated to action
+    pub fn keys(&self, action: &A) -> &[KeySequence] {
+        self.keymap.keys(action)
+    }
+
+    /// Delay to wait for the next key of a sequence
+    pub fn sequence_timeout(&self) -> Duration {
+        self.keymap.sequence_timeout()
     }
 
     /// Get contextual actions.
     /// (just for building a help view)
-    pub fn actions(&self) -> &[Action] {
-        self.0.as_slice()
+    pub fn actions(&self) -> &[A] {
+        self.actions.as_slice()
     }
 }
 
-impl From<Vec<Action>> for Actions {
-    /// Build contextual action
-    ///
-    /// # Panics
-    ///
-    /// If two actions have same key
-    fn from(actions: Vec<Action>) -> Self {
-        // Check key unicity
-        let mut map: HashMap<Key, Vec<Action>> = HashMap::new();
-        for action in actions.iter() {
-            for key in action.keys().iter() {
-                match map.get_mut(key) {
-                    Some(vec) => vec.push(*action),
-                    None => {
-                        map.insert(*key, vec![*action]);
-                    }
-                }
-            }
-        }
-        let errors = map
-            .iter()
-            .filter(|(_, actions)| actions.len() > 1) // at least two actions share same shortcut
-            .map(|(key, actions)| {
-                let actions = actions
-                    .iter()
-                    .map(Action::to_string)
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                format!("Conflict key {} with actions {}", key, actions)
-            })
-            .collect::<Vec<_>>();
-        if !errors.is_empty() {
-            panic!("{}", errors.join("; "))
-        }
+impl<A: Action> TryFrom<Vec<A>> for Actions<A> {
+    type Error = KeymapError<A>;
 
-        // Ok, we can create contextual actions
-        Self(actions)
+    /// Build contextual action with the default keymap
+    fn try_from(actions: Vec<A>) -> Result<Self, Self::Error> {
+        Self::new(actions, &Keymap::default())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TestAction as Action;
+
+    type Actions = super::Actions<Action>;
+    type Keymap = super::Keymap<Action>;
 
     #[test]
     fn should_find_action_by_key() {
-        let actions: Actions = vec![Action::Quit, Action::Sleep].into();
-        let result = actions.find(Key::Ctrl('c'));
+        let actions: Actions = vec![Action::Quit, Action::Sleep].try_into().unwrap();
+        let result = actions.find(&[Key::Ctrl('c')]);
         assert_eq!(result, Some(&Action::Quit));
     }
 
     #[test]
     fn should_find_action_by_key_not_found() {
-        let actions: Actions = vec![Action::Quit, Action::Sleep].into();
-        let result = actions.find(Key::Alt('w'));
+        let actions: Actions = vec![Action::Quit, Action::Sleep].try_into().unwrap();
+        let result = actions.find(&[Key::Alt('w')]);
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn should_find_action_by_custom_key() {
+        let keymap: Keymap = r#"sleep = ["<Space>"]"#.parse().unwrap();
+        let actions = Actions::new(vec![Action::Quit, Action::Sleep], &keymap).unwrap();
+        assert_eq!(actions.find(&[Key::Char(' ')]), Some(&Action::Sleep));
+        assert_eq!(actions.find(&[Key::Char('s')]), None);
+    }
+
+    #[test]
+    fn should_find_action_by_key_sequence() {
+        let keymap: Keymap = r#"sleep = ["<Ctrl+x> <s>"]"#.parse().unwrap();
+        let actions = Actions::new(vec![Action::Quit, Action::Sleep], &keymap).unwrap();
+        assert_eq!(actions.find(&[Key::Ctrl('x')]), None);
+        assert!(actions.is_continued_by(&[Key::Ctrl('x')]));
+        let result = actions.find(&[Key::Ctrl('x'), Key::Char('s')]);
+        assert_eq!(result, Some(&Action::Sleep));
+    }
+
     #[test]
     fn should_create_actions_from_vec() {
-        let _actions: Actions = vec![
+        let actions: Result<Actions, _> = vec![
             Action::Quit,
             Action::Sleep,
             Action::IncrementDelay,
             Action::DecrementDelay,
         ]
-        .into();
+        .try_into();
+        assert!(actions.is_ok());
     }
 
     #[test]
-    #[should_panic]
-    fn should_panic_when_create_actions_conflict_key() {
-        let _actions: Actions = vec![
+    fn should_fail_when_create_actions_conflict_key() {
+        let actions: Result<Actions, _> = vec![
             Action::Quit,
             Action::DecrementDelay,
             Action::Sleep,
@@ -149,6 +163,7 @@ mod tests {
             Action::Quit,
             Action::DecrementDelay,
         ]
-        .into();
+        .try_into();
+        assert!(matches!(actions, Err(KeymapError::Conflicts(_))));
     }
 }
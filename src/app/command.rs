@@ -0,0 +1,311 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use super::input::TextInput;
+use crate::inputs::key::Key;
+
+/// A command of the command line, e.g. `:sleep 7s`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// The arguments, for the completion hint, e.g. `<duration>`
+    pub args: &'static str,
+    pub help: &'static str,
+}
+
+impl CommandSpec {
+    pub const fn new(name: &'static str, args: &'static str, help: &'static str) -> Self {
+        Self { name, args, help }
+    }
+}
+
+/// A parsed command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub name: &'static str,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    Unknown(String),
+    Ambiguous(String, Vec<&'static str>),
+    UnclosedQuote,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Unknown(name) => write!(f, "Unknown command: {}", name),
+            CommandError::Ambiguous(name, names) => {
+                write!(f, "Ambiguous command: {} ({})", name, names.join(", "))
+            }
+            CommandError::UnclosedQuote => write!(f, "Unclosed quote"),
+        }
+    }
+}
+
+impl Error for CommandError {}
+
+/// The result of a key pressed in the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandLineEvent {
+    /// The command line is still open
+    Open,
+    /// The command line is closed without command
+    Closed,
+    /// Run a command
+    Submit(Command),
+    /// The command line cannot be parsed
+    Error(CommandError),
+}
+
+/// A vim-like command line, opened by `:`, with completion and history
+#[derive(Debug, Clone, Default)]
+pub struct CommandLine {
+    input: TextInput,
+    open: bool,
+    /// The prefix being completed, and the index of the last completion
+    completion: Option<(String, usize)>,
+}
+
+impl CommandLine {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.completion = None;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The edited line, if the command line is open
+    pub fn input(&self) -> Option<&TextInput> {
+        self.open.then_some(&self.input)
+    }
+
+    pub fn history(&self) -> &[String] {
+        self.input.history()
+    }
+
+    /// Handle a key press, against the available commands
+    pub fn on_key(&mut self, key: Key, commands: &[CommandSpec]) -> CommandLineEvent {
+        if !self.open {
+            return CommandLineEvent::Closed;
+        }
+        if key != Key::Tab && key != Key::BackTab {
+            self.completion = None;
+        }
+        match key {
+            // Like vim, deleting the empty line closes the command line
+            Key::Esc | Key::Ctrl('c') => self.close(),
+            Key::Backspace if self.input.is_empty() => self.close(),
+            Key::Enter => self.submit(commands),
+            Key::Tab => {
+                self.complete(commands, true);
+                CommandLineEvent::Open
+            }
+            Key::BackTab => {
+                self.complete(commands, false);
+                CommandLineEvent::Open
+            }
+            key => {
+                self.input.on_key(key);
+                CommandLineEvent::Open
+            }
+        }
+    }
+
+    /// Insert a pasted text in the line
+    pub fn paste(&mut self, text: &str) {
+        if self.open {
+            self.completion = None;
+            self.input.paste(text);
+        }
+    }
+
+    fn close(&mut self) -> CommandLineEvent {
+        self.open = false;
+        self.input.set_value("");
+        CommandLineEvent::Closed
+    }
+
+    fn submit(&mut self, commands: &[CommandSpec]) -> CommandLineEvent {
+        self.open = false;
+        // The failed lines are kept in the history too, so they could be fixed
+        let line = self.input.submit();
+        if line.trim().is_empty() {
+            return CommandLineEvent::Closed;
+        }
+        match parse(&line, commands) {
+            Ok(command) => CommandLineEvent::Submit(command),
+            Err(err) => CommandLineEvent::Error(err),
+        }
+    }
+
+    /// Complete the command name, cycling through the candidates
+    fn complete(&mut self, commands: &[CommandSpec], forward: bool) {
+        let value = self.input.value();
+        // Only the command name is completed
+        if value.contains(' ') {
+            return;
+        }
+        let (prefix, index) = match self.completion.take() {
+            Some((prefix, index)) => (prefix, Some(index)),
+            None => (value, None),
+        };
+        let candidates = completions(&prefix, commands);
+        if candidates.is_empty() {
+            return;
+        }
+        let count = candidates.len();
+        let index = match (index, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(index), true) => (index + 1) % count,
+            (Some(index), false) => (index + count - 1) % count,
+        };
+        let mut completed = candidates[index].name.to_string();
+        if count == 1 {
+            completed.push(' ');
+        } else {
+            self.completion = Some((prefix, index));
+        }
+        self.input.set_value(&completed);
+    }
+}
+
+/// The commands starting with the prefix
+pub fn completions<'a>(prefix: &str, commands: &'a [CommandSpec]) -> Vec<&'a CommandSpec> {
+    commands
+        .iter()
+        .filter(|command| command.name.starts_with(prefix.trim_start()))
+        .collect()
+}
+
+/// Parse a command line, the name could be abbreviated, e.g. `:q`,
+/// the arguments are separated by spaces or quoted, e.g. `:notify "Hello world"`
+pub fn parse(line: &str, commands: &[CommandSpec]) -> Result<Command, CommandError> {
+    let mut words = split(line)?.into_iter();
+    let name = words.next().unwrap_or_default();
+    let args = words.collect();
+
+    let name = match commands.iter().find(|command| command.name == name) {
+        Some(command) => command.name,
+        None => match completions(&name, commands).as_slice() {
+            [command] => command.name,
+            [] => return Err(CommandError::Unknown(name)),
+            candidates => {
+                let names = candidates.iter().map(|command| command.name).collect();
+                return Err(CommandError::Ambiguous(name, names));
+            }
+        },
+    };
+    Ok(Command { name, args })
+}
+
+fn split(line: &str) -> Result<Vec<String>, CommandError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err(CommandError::UnclosedQuote);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMANDS: [CommandSpec; 3] = [
+        CommandSpec::new("sleep", "<duration>", "Sleep"),
+        CommandSpec::new("suspend", "", "Suspend"),
+        CommandSpec::new("quit", "", "Quit"),
+    ];
+
+    fn value(command_line: &CommandLine) -> Option<String> {
+        command_line.input().map(TextInput::value)
+    }
+
+    fn command(name: &'static str, args: &[&str]) -> Command {
+        let args = args.iter().map(ToString::to_string).collect();
+        Command { name, args }
+    }
+
+    #[test]
+    fn should_parse_command() {
+        let result = parse("sleep 7s", &COMMANDS);
+        assert_eq!(result, Ok(command("sleep", &["7s"])));
+
+        let result = parse(r#" sl  "7 s" x"#, &COMMANDS);
+        assert_eq!(result, Ok(command("sleep", &["7 s", "x"])));
+
+        assert_eq!(parse("q", &COMMANDS), Ok(command("quit", &[])));
+    }
+
+    #[test]
+    fn should_fail_to_parse_command() {
+        let result = parse("s", &COMMANDS);
+        let expected = CommandError::Ambiguous(String::from("s"), vec!["sleep", "suspend"]);
+        assert_eq!(result, Err(expected));
+
+        let result = parse("exit", &COMMANDS);
+        assert_eq!(result, Err(CommandError::Unknown(String::from("exit"))));
+
+        let result = parse(r#"sleep "7s"#, &COMMANDS);
+        assert_eq!(result, Err(CommandError::UnclosedQuote));
+    }
+
+    #[test]
+    fn should_complete_command() {
+        let mut command_line = CommandLine::default();
+        command_line.open();
+        command_line.on_key(Key::Char('s'), &COMMANDS);
+
+        command_line.on_key(Key::Tab, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some("sleep"));
+        command_line.on_key(Key::Tab, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some("suspend"));
+        command_line.on_key(Key::Tab, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some("sleep"));
+
+        command_line.on_key(Key::Char(' '), &COMMANDS);
+        command_line.on_key(Key::Char('7'), &COMMANDS);
+        let result = command_line.on_key(Key::Enter, &COMMANDS);
+        assert_eq!(result, CommandLineEvent::Submit(command("sleep", &["7"])));
+    }
+
+    #[test]
+    fn should_browse_history() {
+        let mut command_line = CommandLine::default();
+        for line in ["sleep 1", "sleep 2"] {
+            command_line.open();
+            for c in line.chars() {
+                command_line.on_key(Key::Char(c), &COMMANDS);
+            }
+            command_line.on_key(Key::Enter, &COMMANDS);
+        }
+        assert!(!command_line.is_open());
+
+        command_line.open();
+        command_line.on_key(Key::Up, &COMMANDS);
+        command_line.on_key(Key::Up, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some("sleep 1"));
+        command_line.on_key(Key::Down, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some("sleep 2"));
+        command_line.on_key(Key::Down, &COMMANDS);
+        assert_eq!(value(&command_line).as_deref(), Some(""));
+    }
+}
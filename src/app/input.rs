@@ -0,0 +1,465 @@
+use crossterm::event::KeyModifiers;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Paragraph};
+use tui::Frame;
+use unicode_width::UnicodeWidthChar;
+
+use crate::inputs::key::{Key, KeyCode};
+
+/// Number of values kept in the history
+const HISTORY_SIZE: usize = 50;
+
+/// An editable text, single-line or multi-line, with a cursor and a history.
+///
+/// The input is a state kept by its owner, which gives it the keys while it's focused,
+/// see [`TextInput::on_key`], and renders it with [`TextInput::render`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextInput {
+    /// Never empty, a single-line input has one line
+    lines: Vec<Vec<char>>,
+    multiline: bool,
+    /// The line and the column of the cursor, in chars
+    row: usize,
+    col: usize,
+    history: Vec<String>,
+    /// The browsed value of the history
+    history_index: Option<usize>,
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::single_line()
+    }
+}
+
+impl TextInput {
+    /// `<Up>` and `<Down>` browse the history
+    pub fn single_line() -> Self {
+        Self {
+            lines: vec![Vec::new()],
+            multiline: false,
+            row: 0,
+            col: 0,
+            history: Vec::new(),
+            history_index: None,
+        }
+    }
+
+    /// `<Enter>` breaks the line, `<Up>` and `<Down>` move the cursor between the lines
+    pub fn multi_line() -> Self {
+        Self {
+            multiline: true,
+            ..Self::single_line()
+        }
+    }
+
+    /// The text, the lines are separated by `\n`
+    pub fn value(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replace the text, the cursor is moved at the end
+    pub fn set_value(&mut self, value: &str) {
+        self.lines = vec![Vec::new()];
+        self.row = 0;
+        self.col = 0;
+        self.insert_str(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// The line and the column of the cursor, in chars
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// Keep the value in the history, and clear the input
+    pub fn submit(&mut self) -> String {
+        let value = self.value();
+        if !value.trim().is_empty() && self.history.last() != Some(&value) {
+            self.history.push(value.clone());
+            if self.history.len() > HISTORY_SIZE {
+                self.history.remove(0);
+            }
+        }
+        self.history_index = None;
+        self.set_value("");
+        value
+    }
+
+    pub fn history(&self) -> &[String] {
+        self.history.as_slice()
+    }
+
+    /// Handle a key press, returns `false` if the key is not an edition,
+    /// e.g. `<Enter>` in a single-line input, or `<Esc>`
+    pub fn on_key(&mut self, key: Key) -> bool {
+        let (modifiers, code) = match key.decompose() {
+            Some(decomposed) => decomposed,
+            None => return false,
+        };
+        let word = modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match (key, code) {
+            (Key::Char(c), _) => self.insert(c),
+            (Key::Enter, _) if self.multiline => self.break_line(),
+            // Readline shortcuts
+            (Key::Ctrl('a'), _) => self.col = 0,
+            (Key::Ctrl('e'), _) => self.col = self.line().len(),
+            (Key::Alt('b'), _) => self.word_left(),
+            (Key::Alt('f'), _) => self.word_right(),
+            (Key::Ctrl('w'), _) => self.delete_word(),
+            (Key::Ctrl('u'), _) => {
+                let col = self.col;
+                self.line_mut().drain(..col);
+                self.col = 0;
+            }
+            (Key::Ctrl('k'), _) => {
+                let col = self.col;
+                self.line_mut().truncate(col);
+            }
+            (_, KeyCode::Backspace) if word => self.delete_word(),
+            (_, KeyCode::Backspace) => self.backspace(),
+            (_, KeyCode::Delete) => self.delete(),
+            (_, KeyCode::Left) if word => self.word_left(),
+            (_, KeyCode::Right) if word => self.word_right(),
+            (_, KeyCode::Left) => self.left(),
+            (_, KeyCode::Right) => self.right(),
+            (_, KeyCode::Home) => self.col = 0,
+            (_, KeyCode::End) => self.col = self.line().len(),
+            (_, KeyCode::Up) if self.multiline => self.up(),
+            (_, KeyCode::Down) if self.multiline => self.down(),
+            (_, KeyCode::Up) => self.browse_history(true),
+            (_, KeyCode::Down) => self.browse_history(false),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Insert a pasted text at the cursor, a single-line input replaces the line breaks by spaces
+    pub fn paste(&mut self, text: &str) {
+        if self.multiline {
+            self.insert_str(&text.replace("\r\n", "\n"));
+        } else {
+            let text = text
+                .trim_end_matches(['\r', '\n'])
+                .replace(['\r', '\n'], " ");
+            self.insert_str(&text);
+        }
+    }
+
+    /// Render the text, scrolled to show the cursor, the cursor is only shown if focused
+    pub fn render<B: Backend>(
+        &self,
+        rect: &mut Frame<B>,
+        area: Rect,
+        block: Option<Block>,
+        focused: bool,
+    ) {
+        let inner = match &block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        // The scroll is in columns, a wide char takes two columns
+        let cursor_x = width(&self.line()[..self.col]);
+        let scroll_x = (cursor_x + 1).saturating_sub(inner.width as usize);
+        let scroll_y = (self.row + 1).saturating_sub(inner.height as usize);
+        let lines: Vec<_> = self
+            .lines
+            .iter()
+            .skip(scroll_y)
+            .take(inner.height as usize)
+            .map(|line| Spans::from(Span::raw(visible_text(line, scroll_x))))
+            .collect();
+        let mut paragraph = Paragraph::new(lines);
+        if let Some(block) = block {
+            paragraph = paragraph.block(block);
+        }
+        rect.render_widget(paragraph, area);
+
+        if focused && inner.width > 0 && inner.height > 0 {
+            // Both offsets are smaller than the size of the inner area
+            let x = inner.x + (cursor_x - scroll_x) as u16;
+            let y = inner.y + (self.row - scroll_y) as u16;
+            rect.set_cursor(x, y);
+        }
+    }
+
+    fn line(&self) -> &[char] {
+        &self.lines[self.row]
+    }
+
+    fn line_mut(&mut self) -> &mut Vec<char> {
+        &mut self.lines[self.row]
+    }
+
+    fn insert(&mut self, c: char) {
+        let col = self.col;
+        self.line_mut().insert(col, c);
+        self.col += 1;
+    }
+
+    fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' if self.multiline => self.break_line(),
+                '\n' | '\r' => self.insert(' '),
+                c => self.insert(c),
+            }
+        }
+    }
+
+    fn break_line(&mut self) {
+        let col = self.col;
+        let rest = self.line_mut().split_off(col);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let col = self.col;
+            self.line_mut().remove(col);
+        } else if self.row > 0 {
+            // Join the line to the previous one
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line().len();
+            self.line_mut().extend(line);
+        }
+    }
+
+    fn delete(&mut self) {
+        let col = self.col;
+        if col < self.line().len() {
+            self.line_mut().remove(col);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.line_mut().extend(line);
+        }
+    }
+
+    fn delete_word(&mut self) {
+        let end = self.col;
+        self.word_left();
+        let start = self.col;
+        if start == end {
+            // At the beginning of the line
+            self.backspace();
+        } else {
+            self.line_mut().drain(start..end);
+        }
+    }
+
+    fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line().len();
+        }
+    }
+
+    fn right(&mut self) {
+        if self.col < self.line().len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    /// Move to the beginning of the previous word
+    fn word_left(&mut self) {
+        let line = self.line();
+        let mut col = self.col;
+        while col > 0 && !line[col - 1].is_alphanumeric() {
+            col -= 1;
+        }
+        while col > 0 && line[col - 1].is_alphanumeric() {
+            col -= 1;
+        }
+        self.col = col;
+    }
+
+    /// Move to the end of the next word
+    fn word_right(&mut self) {
+        let line = self.line();
+        let mut col = self.col;
+        while col < line.len() && !line[col].is_alphanumeric() {
+            col += 1;
+        }
+        while col < line.len() && line[col].is_alphanumeric() {
+            col += 1;
+        }
+        self.col = col;
+    }
+
+    fn up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.line().len());
+        }
+    }
+
+    fn down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.line().len());
+        }
+    }
+
+    fn browse_history(&mut self, backward: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        let index = match (self.history_index, backward) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < last => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        let value = index
+            .map(|index| self.history[index].clone())
+            .unwrap_or_default();
+        self.set_value(&value);
+        self.history_index = index;
+    }
+}
+
+/// The number of columns of the chars
+fn width(chars: &[char]) -> usize {
+    chars.iter().map(|c| c.width().unwrap_or(0)).sum()
+}
+
+/// The text after the first columns, a wide char cut in half is replaced by spaces
+fn visible_text(line: &[char], scroll_x: usize) -> String {
+    let mut skipped = 0;
+    let mut text = String::new();
+    for &c in line {
+        if skipped < scroll_x {
+            skipped += c.width().unwrap_or(0);
+            text.extend(std::iter::repeat(' ').take(skipped.saturating_sub(scroll_x)));
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    use super::*;
+
+    fn type_str(input: &mut TextInput, text: &str) {
+        for c in text.chars() {
+            input.on_key(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn should_edit_line() {
+        let mut input = TextInput::single_line();
+        type_str(&mut input, "hello world");
+        for key in [Key::Home, Key::Right, Key::Delete, Key::End, Key::Backspace] {
+            assert!(input.on_key(key));
+        }
+        assert_eq!(input.value(), "hllo worl");
+        assert_eq!(input.cursor(), (0, 9));
+
+        assert!(!input.on_key(Key::Enter));
+        assert!(!input.on_key(Key::Esc));
+    }
+
+    #[test]
+    fn should_move_by_word() {
+        let mut input = TextInput::single_line();
+        type_str(&mut input, "sleep for 7s");
+        let ctrl_left = Key::new(KeyModifiers::CONTROL, KeyCode::Left);
+        input.on_key(ctrl_left);
+        assert_eq!(input.cursor(), (0, 10));
+        input.on_key(Key::Alt('b'));
+        assert_eq!(input.cursor(), (0, 6));
+        input.on_key(Key::Alt('f'));
+        assert_eq!(input.cursor(), (0, 9));
+
+        input.on_key(Key::Ctrl('w'));
+        assert_eq!(input.value(), "sleep  7s");
+    }
+
+    #[test]
+    fn should_edit_lines() {
+        let mut input = TextInput::multi_line();
+        type_str(&mut input, "first");
+        input.on_key(Key::Enter);
+        type_str(&mut input, "second");
+        assert_eq!(input.value(), "first\nsecond");
+
+        input.on_key(Key::Up);
+        assert_eq!(input.cursor(), (0, 5));
+        input.on_key(Key::Delete);
+        assert_eq!(input.value(), "firstsecond");
+
+        input.paste("\nthird\r\n");
+        assert_eq!(input.value(), "first\nthird\nsecond");
+    }
+
+    #[test]
+    fn should_paste_in_line() {
+        let mut input = TextInput::single_line();
+        input.paste("sleep\n7s\n");
+        assert_eq!(input.value(), "sleep 7s");
+    }
+
+    #[test]
+    fn should_browse_history() {
+        let mut input = TextInput::single_line();
+        for value in ["one", "two"] {
+            type_str(&mut input, value);
+            assert_eq!(input.submit(), value);
+        }
+        assert!(input.is_empty());
+
+        input.on_key(Key::Up);
+        input.on_key(Key::Up);
+        assert_eq!(input.value(), "one");
+        input.on_key(Key::Down);
+        assert_eq!(input.value(), "two");
+        input.on_key(Key::Down);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_render_cursor_after_wide_chars() {
+        let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+        let mut input = TextInput::single_line();
+        input.paste("文字");
+        let area = Rect::new(0, 0, 10, 1);
+        terminal
+            .draw(|rect| input.render(rect, area, None, true))
+            .unwrap();
+        assert_eq!(terminal.get_cursor().unwrap(), (4, 0));
+
+        // A long line is scrolled by columns, without overflow
+        input.paste(&"x".repeat(70_000));
+        terminal
+            .draw(|rect| input.render(rect, area, None, true))
+            .unwrap();
+        assert_eq!(terminal.get_cursor().unwrap(), (9, 0));
+        assert_eq!(visible_text(&['文', '字', 'x'], 1), " 字x");
+    }
+}
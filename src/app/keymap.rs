@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use std::{fs, io};
+
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+
+use super::actions::Action;
+use crate::inputs::sequence::KeySequence;
+
+/// Default delay to wait for the next key of a sequence
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The keys bound to each action.
+///
+/// Built from the [`Action::default_keys`], a user keymap file could override
+/// the keys of some actions, named by their `Deserialize` names.
+/// A key could be bound to actions which are never available together,
+/// the conflicts are checked on the contextual actions, see [`Actions::new`](super::actions::Actions::new),
+/// and on each set of actions given to [`Keymap::load`], e.g.
+///
+/// ```toml
+/// # delay to wait for the next key of a sequence, in milliseconds
+/// sequence_timeout = 500
+///
+/// quit = ["<Ctrl+c>", "<Esc>"]
+/// sleep = ["<Space>", "<Ctrl+x> <s>"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap<A: Action> {
+    bindings: HashMap<A, Vec<KeySequence>>,
+    sequence_timeout: Duration,
+}
+
+/// The content of a keymap file
+#[derive(Debug, Deserialize)]
+struct KeymapFile {
+    sequence_timeout: Option<u64>,
+    #[serde(flatten)]
+    bindings: HashMap<String, Vec<KeySequence>>,
+}
+
+impl<A: Action> Keymap<A> {
+    /// Load a TOML keymap file, merged over the default bindings,
+    /// then check the keys of each set of actions available together, e.g. on a screen.
+    ///
+    /// Only the TOML format is supported, RON is not
+    pub fn load(path: impl AsRef<Path>, action_sets: &[Vec<A>]) -> Result<Self, KeymapError<A>> {
+        let content = fs::read_to_string(path)?;
+        let keymap = content.parse::<Self>()?;
+        for actions in action_sets {
+            keymap.check(actions)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Replace the keys of the given actions
+    pub fn merge(&mut self, bindings: HashMap<A, Vec<KeySequence>>) {
+        self.bindings.extend(bindings);
+    }
+
+    /// List of key sequences associated to an action
+    pub fn keys(&self, action: &A) -> &[KeySequence] {
+        self.bindings
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Delay to wait for the next key of a sequence
+    pub fn sequence_timeout(&self) -> Duration {
+        self.sequence_timeout
+    }
+
+    /// Check that a key sequence is not bound to several actions,
+    /// nor is the beginning of a sequence of another action
+    pub fn check(&self, actions: &[A]) -> Result<(), KeymapError<A>> {
+        let mut bindings: Vec<(&KeySequence, Vec<A>)> = vec![];
+        for action in actions.iter() {
+            for keys in self.keys(action).iter() {
+                match bindings.iter_mut().find(|(k, _)| *k == keys) {
+                    Some((_, vec)) => vec.push(*action),
+                    None => bindings.push((keys, vec![*action])),
+                }
+            }
+        }
+        let conflicts = bindings
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1) // at least two actions share same shortcut
+            .map(|(keys, actions)| KeyConflict {
+                keys: keys.clone(),
+                actions,
+            })
+            .collect::<Vec<_>>();
+
+        if !conflicts.is_empty() {
+            return Err(KeymapError::Conflicts(conflicts));
+        }
+
+        // The action of the prefix would wait for the sequence timeout
+        let mut prefixes = vec![];
+        for action in actions.iter() {
+            for prefix in self.keys(action).iter() {
+                for other in actions.iter().filter(|other| *other != action) {
+                    let sequences = self.keys(other).iter();
+                    prefixes.extend(
+                        sequences
+                            .filter(|keys| keys.is_continued_by(prefix.keys()))
+                            .map(|keys| KeyPrefix {
+                                prefix: prefix.clone(),
+                                action: *action,
+                                keys: keys.clone(),
+                                other: *other,
+                            }),
+                    );
+                }
+            }
+        }
+        if prefixes.is_empty() {
+            Ok(())
+        } else {
+            Err(KeymapError::Prefixes(prefixes))
+        }
+    }
+}
+
+impl<A: Action> Default for Keymap<A> {
+    fn default() -> Self {
+        let bindings = A::all()
+            .iter()
+            .map(|action| {
+                let keys = action.default_keys().iter().copied().map(KeySequence::from);
+                (*action, keys.collect())
+            })
+            .collect();
+        let sequence_timeout = DEFAULT_SEQUENCE_TIMEOUT;
+        Self {
+            bindings,
+            sequence_timeout,
+        }
+    }
+}
+
+/// Parse a TOML keymap, merged over the default bindings
+impl<A: Action> FromStr for Keymap<A> {
+    type Err = KeymapError<A>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file = toml::from_str::<KeymapFile>(s)?;
+        let bindings = file
+            .bindings
+            .into_iter()
+            .map(|(name, keys)| {
+                let deserializer: StrDeserializer<ValueError> = name.as_str().into_deserializer();
+                let action =
+                    A::deserialize(deserializer).map_err(|_| KeymapError::UnknownAction(name))?;
+                Ok((action, keys))
+            })
+            .collect::<Result<_, KeymapError<A>>>()?;
+
+        let mut keymap = Self::default();
+        keymap.merge(bindings);
+        if let Some(timeout) = file.sequence_timeout {
+            keymap.sequence_timeout = Duration::from_millis(timeout);
+        }
+
+        Ok(keymap)
+    }
+}
+
+/// A key sequence shared by several actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyConflict<A: Action> {
+    pub keys: KeySequence,
+    pub actions: Vec<A>,
+}
+
+impl<A: Action> Display for KeyConflict<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let actions = self
+            .actions
+            .iter()
+            .map(A::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Conflict key {} with actions {}", self.keys, actions)
+    }
+}
+
+/// A key sequence of an action which begins a longer sequence of another action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPrefix<A: Action> {
+    pub prefix: KeySequence,
+    pub action: A,
+    pub keys: KeySequence,
+    pub other: A,
+}
+
+impl<A: Action> Display for KeyPrefix<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Key {} of action {} begins {} of action {}",
+            self.prefix, self.action, self.keys, self.other
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum KeymapError<A: Action> {
+    /// The keymap file cannot be read
+    Io(io::Error),
+    /// The keymap file is not valid
+    Parse(toml::de::Error),
+    /// The keymap file contains an unknown action
+    UnknownAction(String),
+    /// Some key sequences are bound to several actions
+    Conflicts(Vec<KeyConflict<A>>),
+    /// Some key sequences begin a sequence of another action
+    Prefixes(Vec<KeyPrefix<A>>),
+}
+
+impl<A: Action> Display for KeymapError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(err) => write!(f, "Cannot read keymap: {}", err),
+            KeymapError::Parse(err) => write!(f, "Invalid keymap: {}", err),
+            KeymapError::UnknownAction(name) => write!(f, "Unknown action {:?}", name),
+            KeymapError::Conflicts(conflicts) => {
+                let conflicts = conflicts
+                    .iter()
+                    .map(KeyConflict::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                write!(f, "{}", conflicts)
+            }
+            KeymapError::Prefixes(prefixes) => {
+                let prefixes = prefixes
+                    .iter()
+                    .map(KeyPrefix::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                write!(f, "{}", prefixes)
+            }
+        }
+    }
+}
+
+impl<A: Action> Error for KeymapError<A> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KeymapError::Io(err) => Some(err),
+            KeymapError::Parse(err) => Some(err),
+            KeymapError::UnknownAction(_)
+            | KeymapError::Conflicts(_)
+            | KeymapError::Prefixes(_) => None,
+        }
+    }
+}
+
+impl<A: Action> From<io::Error> for KeymapError<A> {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<A: Action> From<toml::de::Error> for KeymapError<A> {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::key::Key;
+    use crate::testing::TestAction as Action;
+
+    type Keymap = super::Keymap<Action>;
+
+    fn sequence(s: &str) -> KeySequence {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn should_use_default_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.keys(&Action::Sleep), &[sequence("<s>")]);
+        assert_eq!(keymap.sequence_timeout(), DEFAULT_SEQUENCE_TIMEOUT);
+    }
+
+    #[test]
+    fn should_override_default_keys() {
+        let keymap: Keymap = r#"
+            sequence_timeout = 500
+            sleep = ["<Space>", "<Ctrl+x> <s>"]
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            keymap.keys(&Action::Sleep),
+            &[sequence("<Space>"), sequence("<Ctrl+x> <s>")]
+        );
+        assert_eq!(
+            keymap.keys(&Action::Quit),
+            Keymap::default().keys(&Action::Quit)
+        );
+        assert_eq!(keymap.sequence_timeout(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn should_reject_invalid_key() {
+        let result = r#"sleep = ["Space"]"#.parse::<Keymap>();
+        assert!(matches!(result, Err(KeymapError::Parse(_))));
+    }
+
+    #[test]
+    fn should_reject_unknown_action() {
+        let result = r#"fly = ["<f>"]"#.parse::<Keymap>();
+        assert!(matches!(result, Err(KeymapError::UnknownAction(name)) if name == "fly"));
+    }
+
+    #[test]
+    fn should_report_conflicts() {
+        let keymap = r#"sleep = ["<q>"]"#.parse::<Keymap>().unwrap();
+        let result = keymap.check(&[Action::Quit, Action::Sleep]);
+        let expected = vec![KeyConflict {
+            keys: Key::Char('q').into(),
+            actions: vec![Action::Quit, Action::Sleep],
+        }];
+        assert!(matches!(result, Err(KeymapError::Conflicts(conflicts)) if conflicts == expected));
+
+        // The actions which are not available together could share a key
+        assert!(keymap
+            .check(&[Action::Sleep, Action::IncrementDelay])
+            .is_ok());
+    }
+
+    #[test]
+    fn should_report_prefixes() {
+        let keymap = r#"sleep = ["<q> <q>"]"#.parse::<Keymap>().unwrap();
+        let result = keymap.check(&[Action::Quit, Action::Sleep]);
+        let expected = vec![KeyPrefix {
+            prefix: Key::Char('q').into(),
+            action: Action::Quit,
+            keys: sequence("<q> <q>"),
+            other: Action::Sleep,
+        }];
+        assert!(matches!(result, Err(KeymapError::Prefixes(prefixes)) if prefixes == expected));
+
+        // An action could be bound to a sequence and its prefix
+        let keymap = r#"sleep = ["<s>", "<s> <s>"]"#.parse::<Keymap>().unwrap();
+        assert!(keymap.check(&[Action::Quit, Action::Sleep]).is_ok());
+    }
+
+    #[test]
+    fn should_check_keymap_file() {
+        let path = std::env::temp_dir().join("plop-tui-keymap-conflict.toml");
+        fs::write(&path, r#"sleep = ["<q>"]"#).unwrap();
+
+        let result = Keymap::load(&path, &[vec![Action::Quit, Action::Sleep]]);
+        assert!(matches!(result, Err(KeymapError::Conflicts(_))));
+        let result = Keymap::load(&path, &[vec![Action::Quit], vec![Action::Sleep]]);
+        assert!(result.is_ok());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Instant;
+
+use super::actions::{Action, Actions};
+use crate::inputs::key::Key;
+
+/// The result of a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMatch<A> {
+    /// The keys match an action
+    Action(A),
+    /// The keys are the beginning of a sequence, wait for the next key
+    Pending,
+    /// No action associated to the keys
+    NotFound,
+}
+
+/// Match key sequences, e.g. `<g> <g>`, against contextual actions
+///
+/// The pressed keys are kept until they match an action, or until the sequence timeout
+/// expires, see [`KeyMatcher::on_tick`].
+#[derive(Debug, Default, Clone)]
+pub struct KeyMatcher {
+    pending: Vec<Key>,
+    last_key: Option<Instant>,
+    replay: Option<Key>,
+}
+
+impl KeyMatcher {
+    /// The keys of the current partial sequence
+    pub fn pending(&self) -> &[Key] {
+        self.pending.as_slice()
+    }
+
+    /// The key which has broken a sequence, to handle after the action of the sequence prefix
+    pub fn take_replay(&mut self) -> Option<Key> {
+        self.replay.take()
+    }
+
+    /// Handle a key press
+    pub fn on_key<A: Action>(&mut self, actions: &Actions<A>, key: Key) -> KeyMatch<A> {
+        let prefix = self.pending.clone();
+        self.pending.push(key);
+        match self.find(actions) {
+            KeyMatch::NotFound if !prefix.is_empty() => {
+                // The sequence is broken, the prefix runs its action as on timeout,
+                // then the last key is retried alone
+                match actions.find(&prefix).copied() {
+                    Some(action) => {
+                        self.replay = Some(key);
+                        KeyMatch::Action(action)
+                    }
+                    None => self.on_key(actions, key),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Check the sequence timeout,
+    /// an expired partial sequence run its action if the keys match one
+    pub fn on_tick<A: Action>(&mut self, actions: &Actions<A>) -> KeyMatch<A> {
+        let expired = self
+            .last_key
+            .map(|instant| instant.elapsed() >= actions.sequence_timeout())
+            .unwrap_or_default();
+        if !expired {
+            return KeyMatch::NotFound;
+        }
+
+        let result = actions.find(&self.pending).copied();
+        self.reset();
+        result.map(KeyMatch::Action).unwrap_or(KeyMatch::NotFound)
+    }
+
+    fn find<A: Action>(&mut self, actions: &Actions<A>) -> KeyMatch<A> {
+        if actions.is_continued_by(&self.pending) {
+            // Wait for the next key, or the timeout
+            self.last_key = Some(Instant::now());
+            return KeyMatch::Pending;
+        }
+
+        let result = actions.find(&self.pending).copied();
+        self.reset();
+        result.map(KeyMatch::Action).unwrap_or(KeyMatch::NotFound)
+    }
+
+    fn reset(&mut self) {
+        self.pending.clear();
+        self.last_key = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::keymap::Keymap;
+    use crate::testing::TestAction;
+
+    fn actions(keymap: &str) -> Actions<TestAction> {
+        let keymap: Keymap<TestAction> = keymap.parse().unwrap();
+        let actions = TestAction::all().to_vec();
+        Actions::new(actions, &keymap).unwrap()
+    }
+
+    #[test]
+    fn should_match_single_key() {
+        let actions = actions("");
+        let mut matcher = KeyMatcher::default();
+        let result = matcher.on_key(&actions, Key::Char('s'));
+        assert_eq!(result, KeyMatch::Action(TestAction::Sleep));
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn should_match_sequence() {
+        let actions = actions(r#"sleep = ["<Ctrl+x> <Ctrl+s>"]"#);
+        let mut matcher = KeyMatcher::default();
+
+        let result = matcher.on_key(&actions, Key::Ctrl('x'));
+        assert_eq!(result, KeyMatch::Pending);
+        assert_eq!(matcher.pending(), &[Key::Ctrl('x')]);
+
+        let result = matcher.on_key(&actions, Key::Ctrl('s'));
+        assert_eq!(result, KeyMatch::Action(TestAction::Sleep));
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn should_retry_last_key_when_sequence_is_broken() {
+        let actions = actions(r#"sleep = ["<g> <s>"]"#);
+        let mut matcher = KeyMatcher::default();
+
+        matcher.on_key(&actions, Key::Char('g'));
+        let result = matcher.on_key(&actions, Key::Char('q'));
+        assert_eq!(result, KeyMatch::Action(TestAction::Quit));
+    }
+
+    #[test]
+    fn should_run_prefix_action_when_sequence_is_broken() {
+        let actions = actions(r#"sleep = ["<g>", "<g> <g>"]"#);
+        let mut matcher = KeyMatcher::default();
+
+        assert_eq!(matcher.on_key(&actions, Key::Char('g')), KeyMatch::Pending);
+        let result = matcher.on_key(&actions, Key::Char('q'));
+        assert_eq!(result, KeyMatch::Action(TestAction::Sleep));
+        assert!(matcher.pending().is_empty());
+
+        let key = matcher.take_replay().unwrap();
+        assert_eq!(key, Key::Char('q'));
+        assert_eq!(matcher.take_replay(), None);
+        let result = matcher.on_key(&actions, key);
+        assert_eq!(result, KeyMatch::Action(TestAction::Quit));
+    }
+
+    #[test]
+    fn should_run_prefix_action_on_timeout() {
+        let actions = actions(
+            r#"
+            sequence_timeout = 0
+            sleep = ["<s>", "<s> <s>"]
+            "#,
+        );
+        let mut matcher = KeyMatcher::default();
+
+        let result = matcher.on_key(&actions, Key::Char('s'));
+        assert_eq!(result, KeyMatch::Pending);
+
+        let result = matcher.on_tick(&actions);
+        assert_eq!(result, KeyMatch::Action(TestAction::Sleep));
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn should_drop_partial_sequence_on_timeout() {
+        let actions = actions(
+            r#"
+            sequence_timeout = 0
+            sleep = ["<g> <s>"]
+            "#,
+        );
+        let mut matcher = KeyMatcher::default();
+
+        matcher.on_key(&actions, Key::Char('g'));
+        let result = matcher.on_tick(&actions);
+        assert_eq!(result, KeyMatch::NotFound);
+        assert!(matcher.pending().is_empty());
+    }
+}
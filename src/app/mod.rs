@@ -1,121 +1,801 @@
+use std::time::Instant;
+
 use log::{debug, error, warn};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tui_logger::{TuiWidgetEvent, TuiWidgetState};
 
 use self::actions::Actions;
-use self::state::AppState;
-use crate::app::actions::Action;
+use self::command::{CommandLine, CommandLineEvent};
+use self::keymap::Keymap;
+use self::matcher::{KeyMatch, KeyMatcher};
+use self::modal::{Modal, ModalEvent};
+use self::model::{Cmd, Model, ModelMsg, Msg};
+use self::notifications::{Notifications, Severity};
+use self::palette::{Palette, PaletteEvent};
+use self::screens::Screens;
+use self::tasks::Tasks;
+use self::ui::{MouseTarget, UiAreas};
 use crate::inputs::key::Key;
-use crate::io::IoEvent;
+use crate::inputs::mouse::Mouse;
+use crate::io::{IoMessage, IoRequest, TaskStatus};
 
 pub mod actions;
-pub mod state;
+pub mod command;
+pub mod input;
+pub mod keymap;
+pub mod matcher;
+pub mod modal;
+pub mod model;
+pub mod notifications;
+pub mod palette;
+pub mod screens;
+pub mod tasks;
 pub mod ui;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum AppReturn {
     Exit,
+    Suspend,
     Continue,
 }
 
-/// The main application, containing the state
-pub struct App {
+/// The main application, wrapping the [`Model`] of the user
+pub struct App<M: Model> {
+    /// Only taken while it's updated
+    model: Option<M>,
+    /// The displayed screens
+    screens: Screens<M::Screen>,
+    /// The modal displayed over the screen, capturing the keys
+    modal: Option<Modal<M::Action>>,
+    /// Ask before exiting while some tasks are running
+    confirm_exit: bool,
+    /// The command palette, capturing the keys
+    palette: Option<Palette<M::Action>>,
+    /// The command line, capturing the keys while it's open
+    command_line: CommandLine,
+    /// The focused input of the model has not handled `Esc`, the keys run the actions
+    focus_released: bool,
     /// We could dispatch an IO event
-    io_tx: tokio::sync::mpsc::Sender<IoEvent>,
+    io_tx: mpsc::Sender<IoRequest<M::Event>>,
     /// Contextual actions
-    actions: Actions,
+    actions: Actions<M::Action>,
+    /// Keys bound to actions
+    keymap: Keymap<M::Action>,
+    /// Pending keys of a sequence
+    matcher: KeyMatcher,
     /// State
-    is_loading: bool,
-    state: AppState,
+    tasks: Tasks<M::Event>,
+    notifications: Notifications,
+    /// UI
+    areas: UiAreas,
+    show_errors: bool,
+    logs_state: TuiWidgetState,
+    /// Something has changed since the last drawn frame
+    dirty: bool,
+    /// When the last frame has been drawn
+    last_frame: Option<Instant>,
 }
 
-impl App {
-    pub fn new(io_tx: tokio::sync::mpsc::Sender<IoEvent>) -> Self {
-        let actions = vec![Action::Quit].into();
-        let is_loading = false;
-        let state = AppState::default();
+impl<M: Model> App<M> {
+    pub fn new(
+        model: M,
+        io_tx: mpsc::Sender<IoRequest<M::Event>>,
+        keymap: Keymap<M::Action>,
+    ) -> Self {
+        let actions = Actions::default();
+        let matcher = KeyMatcher::default();
+        let tasks = Tasks::default();
+        let notifications = Notifications::default();
+        let areas = UiAreas::default();
+        let logs_state = TuiWidgetState::new();
 
-        Self {
+        let mut app = Self {
+            model: Some(model),
+            screens: Screens::default(),
+            modal: None,
+            confirm_exit: false,
+            palette: None,
+            command_line: CommandLine::default(),
+            focus_released: false,
             io_tx,
             actions,
-            is_loading,
-            state,
-        }
+            keymap,
+            matcher,
+            tasks,
+            notifications,
+            areas,
+            show_errors: false,
+            logs_state,
+            dirty: true,
+            last_frame: None,
+        };
+        app.update_actions();
+        app
+    }
+
+    /// Ask for a confirmation before exiting while some tasks are running
+    #[must_use]
+    pub fn confirm_exit(mut self, confirm_exit: bool) -> Self {
+        self.confirm_exit = confirm_exit;
+        self
+    }
+
+    /// Initialize the model, before the first frame
+    pub async fn init(&mut self) -> AppReturn {
+        self.update(Msg::Init).await
     }
 
     /// Handle a user action
     pub async fn do_action(&mut self, key: Key) -> AppReturn {
-        if let Some(action) = self.actions.find(key) {
-            debug!("Run action [{:?}]", action);
-            match action {
-                Action::Quit => AppReturn::Exit,
-                Action::Sleep => {
-                    if let Some(duration) = self.state.duration().cloned() {
-                        // Sleep is an I/O action, we dispatch on the IO channel that's run on another thread
-                        self.dispatch(IoEvent::Sleep(duration)).await
-                    }
-                    AppReturn::Continue
-                }
-                // IncrementDelay and DecrementDelay is handled in the UI thread
-                Action::IncrementDelay => {
-                    self.state.increment_delay();
-                    AppReturn::Continue
-                }
-                // Note, that we clamp the duration, so we stay >= 0
-                Action::DecrementDelay => {
-                    self.state.decrement_delay();
-                    AppReturn::Continue
-                }
+        let result = self.on_key(key).await;
+        // The key which has broken a sequence is handled after the action of the prefix
+        match self.matcher.take_replay() {
+            Some(key) if result == AppReturn::Continue => self.on_key(key).await,
+            _ => result,
+        }
+    }
+
+    async fn on_key(&mut self, key: Key) -> AppReturn {
+        self.dirty = true;
+        if let Some(modal) = &mut self.modal {
+            let event = modal.on_key(key);
+            return self.on_modal_event(event).await;
+        }
+        if let Some(palette) = &mut self.palette {
+            let event = palette.on_key(key);
+            return self.on_palette_event(event).await;
+        }
+        if self.command_line.is_open() {
+            let commands = self.model().commands(self.screens.current());
+            let event = self.command_line.on_key(key, &commands);
+            return self.on_command_line_event(event).await;
+        }
+        // The focused text input of the model gets the keys
+        if self.has_focus() {
+            let result = self.update(Msg::Key(key)).await;
+            // Keep a way out of an input which does not handle Esc
+            if key == Key::Esc && self.has_focus() {
+                warn!(
+                    "The focused input has not handled {}, the keys run the actions",
+                    key
+                );
+                self.focus_released = true;
+            }
+            return result;
+        }
+        match self.matcher.on_key(&self.actions, key) {
+            KeyMatch::Action(action) => self.run_action(action).await,
+            KeyMatch::Pending => {
+                debug!(
+                    "Waiting for the next key after {:?}",
+                    self.matcher.pending()
+                );
+                AppReturn::Continue
+            }
+            // An unbound Esc goes back to the previous screen
+            KeyMatch::NotFound if key == Key::Esc && self.screens.can_pop() => {
+                self.pop_screen();
+                AppReturn::Continue
+            }
+            // An unbound Ctrl+p opens the command palette
+            KeyMatch::NotFound if key == Key::Ctrl('p') => {
+                self.palette = Some(Palette::new(self.actions.actions()));
+                AppReturn::Continue
+            }
+            // An unbound `:` opens the command line, if the screen has some commands
+            KeyMatch::NotFound
+                if key == Key::Char(':')
+                    && !self.model().commands(self.screens.current()).is_empty() =>
+            {
+                self.command_line.open();
+                AppReturn::Continue
+            }
+            KeyMatch::NotFound => {
+                warn!("No action accociated to {}", key);
+                AppReturn::Continue
             }
+        }
+    }
+
+    /// Give a pasted text to the focused text input, the pasted text never runs actions
+    pub async fn do_paste(&mut self, text: String) -> AppReturn {
+        self.dirty = true;
+        if let Some(modal) = &mut self.modal {
+            modal.paste(&text);
+        } else if let Some(palette) = &mut self.palette {
+            palette.paste(&text);
+        } else if self.command_line.is_open() {
+            self.command_line.paste(&text);
+        } else if self.has_focus() {
+            return self.update(Msg::Paste(text)).await;
         } else {
-            warn!("No action accociated to {}", key);
-            AppReturn::Continue
+            debug!("Ignore the pasted text, no text input is focused");
         }
+        AppReturn::Continue
+    }
+
+    /// Handle a mouse event, against the areas of the last drawn UI
+    pub async fn do_mouse(&mut self, mouse: Mouse) -> AppReturn {
+        // The modal, the palette and the command line capture the inputs
+        if self.modal.is_some() || self.palette.is_some() || self.command_line.is_open() {
+            return AppReturn::Continue;
+        }
+        // Nothing to redraw when nothing is hit, e.g. by a drag
+        let Some(target) = self.areas.target(mouse) else {
+            return AppReturn::Continue;
+        };
+        self.dirty = true;
+        match target {
+            MouseTarget::HelpRow(row) => {
+                if let Some(action) = ui::help_action(&self.actions, row) {
+                    return self.run_action(action).await;
+                }
+            }
+            MouseTarget::LogsUp => {
+                self.logs_state.transition(&TuiWidgetEvent::PrevPageKey);
+            }
+            MouseTarget::LogsDown => {
+                self.logs_state.transition(&TuiWidgetEvent::NextPageKey);
+            }
+        }
+        AppReturn::Continue
+    }
+
+    /// Close the modal once it's answered, and deliver its result
+    async fn on_modal_event(&mut self, event: ModalEvent<M::Action>) -> AppReturn {
+        if event != ModalEvent::Open {
+            self.modal = None;
+        }
+        match event {
+            ModalEvent::Open | ModalEvent::Closed => AppReturn::Continue,
+            ModalEvent::Action(action) => self.run_action(action).await,
+            ModalEvent::Input(action, input) => {
+                debug!("Submit [{:?}] with {:?}", action, input);
+                self.update(Msg::Input(action, input)).await
+            }
+            ModalEvent::Exit => AppReturn::Exit,
+        }
+    }
+
+    /// Run the action selected in the palette, if it's contextual
+    async fn on_palette_event(&mut self, event: PaletteEvent<M::Action>) -> AppReturn {
+        if event != PaletteEvent::Open {
+            self.palette = None;
+        }
+        match event {
+            PaletteEvent::Run(action) if self.actions.actions().contains(&action) => {
+                self.run_action(action).await
+            }
+            PaletteEvent::Run(action) => {
+                warn!("Action [{:?}] is not available", action);
+                self.notify(
+                    Severity::Warning,
+                    format!("{} is not available here", action),
+                );
+                AppReturn::Continue
+            }
+            PaletteEvent::Open | PaletteEvent::Closed => AppReturn::Continue,
+        }
+    }
+
+    /// Run the submitted command, the errors are notified
+    async fn on_command_line_event(&mut self, event: CommandLineEvent) -> AppReturn {
+        match event {
+            CommandLineEvent::Submit(command) => {
+                debug!("Run command {:?}", command);
+                self.update(Msg::Command(command)).await
+            }
+            CommandLineEvent::Error(err) => {
+                warn!("Invalid command: {}", err);
+                self.notify(Severity::Error, err.to_string());
+                AppReturn::Continue
+            }
+            CommandLineEvent::Open | CommandLineEvent::Closed => AppReturn::Continue,
+        }
+    }
+
+    async fn run_action(&mut self, action: M::Action) -> AppReturn {
+        debug!("Run action [{:?}]", action);
+        self.update(Msg::Action(action)).await
+    }
+
+    /// The next draw use the new size of the terminal
+    pub async fn on_resize(&mut self, width: u16, height: u16) -> AppReturn {
+        debug!("Terminal resized to {}x{}", width, height);
+        self.dirty = true;
+        AppReturn::Continue
     }
 
     /// We could update the app or dispatch event on tick
     pub async fn update_on_tick(&mut self) -> AppReturn {
-        // here we just increment a counter
-        self.state.incr_tick();
+        // Nothing is drawn while the app is idle
+        if self.notifications.expire(Instant::now()) {
+            self.dirty = true;
+        }
+        if let result @ (AppReturn::Exit | AppReturn::Suspend) = self.update(Msg::Tick).await {
+            return result;
+        }
+
+        // A partial key sequence could have expired
+        let is_pending = !self.matcher.pending().is_empty();
+        if let KeyMatch::Action(action) = self.matcher.on_tick(&self.actions) {
+            return self.run_action(action).await;
+        }
+        if is_pending && self.matcher.pending().is_empty() {
+            self.dirty = true;
+        }
         AppReturn::Continue
     }
 
-    /// Send a network event to the IO thread
-    pub async fn dispatch(&mut self, action: IoEvent) {
-        // `is_loading` will be set to false again after the async action has finished in io/handler.rs
-        self.is_loading = true;
-        if let Err(e) = self.io_tx.send(action).await {
-            self.is_loading = false;
-            error!("Error from dispatch {}", e);
+    /// Apply the result of an IO event
+    pub async fn on_io_message(&mut self, message: IoMessage<M::Outcome, M::Error>) -> AppReturn {
+        self.dirty = true;
+        match message {
+            IoMessage::Task(id, status) => {
+                if status == TaskStatus::Cancelled {
+                    self.notify(Severity::Info, format!("Task {} cancelled", id));
+                }
+                self.tasks.update(id, status);
+            }
+            IoMessage::Progress(id, progress) => {
+                self.tasks.progress(id, progress);
+            }
+            IoMessage::Retry(id, retry) => {
+                let message = format!(
+                    "Task {} failed: {}, retry {}/{} in {:.1}s",
+                    id,
+                    retry.error,
+                    retry.retry,
+                    retry.max_retries,
+                    retry.delay.as_secs_f64()
+                );
+                self.tasks.retry(id, retry);
+                self.notify(Severity::Warning, message);
+            }
+            IoMessage::Finished(id, result) => {
+                match &result {
+                    Ok(_) => self.tasks.update(id, TaskStatus::Done),
+                    Err(err) => {
+                        self.tasks.update(id, TaskStatus::Failed);
+                        self.notify(Severity::Error, format!("Task {} failed: {}", id, err));
+                    }
+                }
+                return self.update(Msg::Outcome(id, result)).await;
+            }
+        }
+        AppReturn::Continue
+    }
+
+    /// Update the model, then run its commands and refresh the contextual actions
+    async fn update(&mut self, msg: ModelMsg<M>) -> AppReturn {
+        let is_tick = matches!(msg, Msg::Tick);
+        let was_focused = self.model().is_focused(self.screens.current());
+        let model = self
+            .model
+            .take()
+            .expect("the model is set back after each update");
+        let (model, cmd) = model.update(self.screens.current(), msg);
+        self.model = Some(model);
+
+        // A tick only draws a frame if the model requests some commands, e.g. `Cmd::Redraw`
+        let cmds = cmd.flatten();
+        if !is_tick || !cmds.is_empty() {
+            self.dirty = true;
+        }
+
+        let mut result = AppReturn::Continue;
+        for cmd in cmds {
+            match cmd {
+                Cmd::Dispatch(event) => self.dispatch(event),
+                Cmd::CancelLastTask => self.cancel_last_task(),
+                Cmd::Notify(severity, message) => self.notify(severity, message),
+                Cmd::ToggleErrors => self.show_errors = !self.show_errors,
+                Cmd::Push(screen) => {
+                    debug!("Push screen {:?}", screen);
+                    self.screens.push(screen);
+                    self.focus_released = false;
+                }
+                Cmd::Pop => self.pop_screen(),
+                Cmd::Replace(screen) => {
+                    debug!("Replace screen by {:?}", screen);
+                    self.screens.replace(screen);
+                    self.focus_released = false;
+                }
+                // Exiting wins over suspending
+                Cmd::Suspend if result == AppReturn::Continue => result = AppReturn::Suspend,
+                Cmd::Modal(modal) => self.modal = Some(modal),
+                Cmd::Focus => self.focus_released = false,
+                Cmd::Exit if self.confirm_exit && self.tasks.is_active() => {
+                    let count = self.tasks.count(TaskStatus::Queued)
+                        + self.tasks.count(TaskStatus::Running);
+                    self.modal = Some(Modal::confirm_exit(count));
+                }
+                Cmd::Exit => result = AppReturn::Exit,
+                Cmd::None | Cmd::Batch(_) | Cmd::Redraw | Cmd::Suspend => {}
+            }
+        }
+        self.update_actions();
+        // The keys are given back once the model has changed its focus
+        if self.model().is_focused(self.screens.current()) != was_focused {
+            self.focus_released = false;
+        }
+        result
+    }
+
+    /// Run an IO event as a new task
+    ///
+    /// The UI loop never waits for the IO loop, a full queue fails the task
+    fn dispatch(&mut self, event: M::Event) {
+        // The task status will be updated by the IO messages
+        let id = self.tasks.push(event.clone());
+        match self.io_tx.try_send(IoRequest::Run(id, event)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!("Cannot dispatch task {}, the queue is full", id);
+                self.tasks.update(id, TaskStatus::Failed);
+                self.notify(
+                    Severity::Error,
+                    format!("Cannot run task {}, the queue is full", id),
+                );
+            }
+            Err(e) => {
+                error!("Error from dispatch {}", e);
+                self.tasks.update(id, TaskStatus::Failed);
+                self.notify(Severity::Error, format!("Cannot run task {}", id));
+            }
+        }
+    }
+
+    /// Cancel the last dispatched task still queued or running
+    fn cancel_last_task(&mut self) {
+        let id = match self.tasks.last_active() {
+            Some(task) => task.id,
+            None => {
+                warn!("No task to cancel");
+                self.notify(Severity::Warning, "No task to cancel");
+                return;
+            }
         };
+        match self.io_tx.try_send(IoRequest::Cancel(id)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!("Cannot cancel task {}, the queue is full", id);
+                self.notify(
+                    Severity::Warning,
+                    format!("Cannot cancel task {}, the queue is full", id),
+                );
+            }
+            Err(e) => error!("Error from cancel {}", e),
+        }
+    }
+
+    fn pop_screen(&mut self) {
+        if let Some(screen) = self.screens.pop() {
+            debug!("Pop screen {:?}", screen);
+        }
+        self.focus_released = false;
+        self.update_actions();
+    }
+
+    /// The keys are given to the focused input of the model
+    fn has_focus(&self) -> bool {
+        !self.focus_released && self.model().is_focused(self.screens.current())
+    }
+
+    /// Update contextual actions, with the default keys if some keys conflict,
+    /// the actions of the previous screen would run against the new one
+    fn update_actions(&mut self) {
+        let actions = self.model().actions(self.screens.current());
+        if actions == self.actions.actions() {
+            return;
+        }
+        match Actions::new(actions.clone(), &self.keymap) {
+            Ok(actions) => self.actions = actions,
+            Err(err) => {
+                error!("Invalid contextual actions: {}", err);
+                self.notify(
+                    Severity::Error,
+                    format!("{}, the default keys are used", err),
+                );
+                self.actions =
+                    Actions::new(actions.clone(), &Keymap::default()).unwrap_or_else(|err| {
+                        error!("Invalid default keys: {}", err);
+                        Actions::unchecked(actions, Keymap::default())
+                    });
+            }
+        }
     }
 
-    pub fn actions(&self) -> &Actions {
+    pub fn model(&self) -> &M {
+        self.model
+            .as_ref()
+            .expect("the model is set back after each update")
+    }
+
+    pub fn screens(&self) -> &Screens<M::Screen> {
+        &self.screens
+    }
+
+    /// The modal displayed over the screen
+    pub fn modal(&self) -> Option<&Modal<M::Action>> {
+        self.modal.as_ref()
+    }
+
+    /// The command palette
+    pub fn palette(&self) -> Option<&Palette<M::Action>> {
+        self.palette.as_ref()
+    }
+
+    pub fn command_line(&self) -> &CommandLine {
+        &self.command_line
+    }
+
+    /// The keys bound to every action
+    pub fn keymap(&self) -> &Keymap<M::Action> {
+        &self.keymap
+    }
+
+    pub fn actions(&self) -> &Actions<M::Action> {
         &self.actions
     }
-    pub fn state(&self) -> &AppState {
-        &self.state
+
+    /// The keys of the current partial sequence
+    pub fn pending_keys(&self) -> &[Key] {
+        self.matcher.pending()
+    }
+
+    /// Keep the areas of the drawn UI
+    pub fn set_areas(&mut self, areas: UiAreas) {
+        self.areas = areas;
+    }
+
+    pub fn logs_state(&self) -> &TuiWidgetState {
+        &self.logs_state
+    }
+
+    /// Request a new frame, e.g. after the terminal has been cleared
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether a new frame is required, reset the flag.
+    /// The running tasks are redrawn at each spinner frame, if the screen shows them
+    pub fn take_dirty(&mut self) -> bool {
+        let spinning = self.shows_spinner()
+            && self
+                .last_frame
+                .map_or(true, |drawn| drawn.elapsed() >= ui::SPINNER_PERIOD);
+        let dirty = std::mem::take(&mut self.dirty) || spinning;
+        if dirty {
+            self.last_frame = Some(Instant::now());
+        }
+        dirty
+    }
+
+    /// Some running tasks are displayed with their spinner
+    fn shows_spinner(&self) -> bool {
+        let panels = self.model().panels(self.screens.current());
+        panels.tasks && !self.show_errors && self.tasks.running().next().is_some()
     }
 
+    /// Some IO tasks are queued or running
     pub fn is_loading(&self) -> bool {
-        self.is_loading
+        self.tasks.is_active()
+    }
+
+    pub fn tasks(&self) -> &Tasks<M::Event> {
+        &self.tasks
+    }
+
+    /// Display a toast to the user
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        self.notifications.push(severity, message);
+        self.dirty = true;
+    }
+
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+
+    /// The error history is displayed instead of the body
+    pub fn show_errors(&self) -> bool {
+        self.show_errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tui::layout::Rect;
+
+    use super::*;
+    use crate::testing::{
+        test_app, test_app_with, type_keys, TestAction, TestModel, TestScreen, TEST_QUEUE,
+    };
+
+    #[tokio::test]
+    async fn should_confirm_exit_while_tasks_are_running() {
+        let (app, _io_rx) = test_app();
+        let mut app = app.confirm_exit(true);
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Exit);
+
+        app.do_action(Key::Char('s')).await;
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Continue);
+        assert!(app.modal().is_some());
+
+        // The modal captures the keys
+        assert_eq!(app.do_action(Key::Char('n')).await, AppReturn::Continue);
+        assert!(app.modal().is_none());
+        app.do_action(Key::Char('q')).await;
+        assert_eq!(app.do_action(Key::Char('y')).await, AppReturn::Exit);
     }
 
-    pub fn initialized(&mut self) {
-        // Update contextual actions
-        self.actions = vec![
-            Action::Quit,
-            Action::Sleep,
-            Action::IncrementDelay,
-            Action::DecrementDelay,
-        ]
-        .into();
-        self.state = AppState::initialized()
+    #[tokio::test]
+    async fn should_pop_screen_on_esc() {
+        let (mut app, _io_rx) = test_app();
+        assert_eq!(app.actions().actions().len(), 4);
+
+        type_keys(&mut app, ":open\n").await;
+        assert_eq!(app.screens().current(), &TestScreen::Detail);
+        assert_eq!(app.actions().actions(), &[TestAction::Quit]);
+
+        app.do_action(Key::Esc).await;
+        assert_eq!(app.screens().current(), &TestScreen::Main);
+        assert_eq!(app.actions().actions().len(), 4);
+
+        // The root screen is kept
+        app.do_action(Key::Esc).await;
+        assert_eq!(app.screens().depth(), 1);
     }
 
-    pub fn loaded(&mut self) {
-        self.is_loading = false;
+    #[tokio::test]
+    async fn should_not_draw_idle_app_on_tick() {
+        let (mut app, _io_rx) = test_app();
+        assert!(app.take_dirty());
+
+        app.update_on_tick().await;
+        assert!(!app.take_dirty());
+
+        app.notify(Severity::Info, "Hello");
+        assert!(app.take_dirty());
+        app.update_on_tick().await;
+        assert!(!app.take_dirty());
     }
 
-    pub fn slept(&mut self) {
-        self.state.incr_sleep();
+    #[tokio::test]
+    async fn should_draw_spinner_at_its_frame_rate() {
+        let (mut app, mut io_rx) = test_app();
+        app.do_action(Key::Char('s')).await;
+        let Ok(IoRequest::Run(id, _)) = io_rx.try_recv() else {
+            panic!("The sleep is not dispatched");
+        };
+        app.on_io_message(IoMessage::Task(id, TaskStatus::Running))
+            .await;
+
+        assert!(app.take_dirty());
+        assert!(!app.take_dirty());
+        std::thread::sleep(ui::SPINNER_PERIOD);
+        assert!(app.take_dirty());
+
+        // The detail screen does not show the tasks
+        type_keys(&mut app, ":open\n").await;
+        assert!(app.take_dirty());
+        std::thread::sleep(ui::SPINNER_PERIOD);
+        assert!(!app.take_dirty());
+    }
+
+    #[tokio::test]
+    async fn should_release_focus_on_unhandled_esc() {
+        let model = TestModel {
+            focused: true,
+            ..TestModel::default()
+        };
+        let (mut app, _io_rx) = test_app_with(model, Keymap::default());
+
+        // The focused model gets the keys, but ignores Esc
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Continue);
+        app.do_action(Key::Esc).await;
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Exit);
+
+        // The model takes the keys back
+        type_keys(&mut app, ":focus\n").await;
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Continue);
+    }
+
+    #[tokio::test]
+    async fn should_run_action_clicked_in_help() {
+        let (mut app, _io_rx) = test_app();
+        app.set_areas(UiAreas {
+            help: Rect::new(60, 3, 32, 20),
+            logs: Rect::new(0, 30, 100, 10),
+        });
+
+        // The rows: <Ctrl+c> and <q> quit, <s> sleeps, <+> increments the delay
+        app.do_mouse(Mouse::Click(70, 7)).await;
+        assert_eq!(app.model().delay, Duration::from_secs(1));
+
+        // The top border, and the other areas, run nothing
+        app.do_mouse(Mouse::Click(70, 3)).await;
+        app.do_mouse(Mouse::Click(10, 7)).await;
+        app.do_mouse(Mouse::ScrollDown(70, 7)).await;
+        app.do_mouse(Mouse::Drag(70, 7)).await;
+        assert_eq!(app.model().delay, Duration::from_secs(1));
+        assert_eq!(app.do_mouse(Mouse::Click(70, 4)).await, AppReturn::Exit);
+    }
+
+    #[tokio::test]
+    async fn should_not_run_action_hidden_in_help() {
+        let (mut app, mut io_rx) = test_app();
+        app.set_areas(UiAreas {
+            help: Rect::new(60, 3, 32, 4),
+            logs: Rect::new(0, 30, 100, 10),
+        });
+
+        // Only the two quit rows are visible, the bottom border hides the sleep row
+        assert_eq!(app.do_mouse(Mouse::Click(70, 6)).await, AppReturn::Continue);
+        assert!(io_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn should_use_default_keys_on_conflict() {
+        let keymap = r#"sleep = ["<q>"]"#.parse().unwrap();
+        let (mut app, _io_rx) = test_app_with(TestModel::default(), keymap);
+
+        assert_eq!(app.actions().actions().len(), 4);
+        assert_eq!(app.notifications().history().count(), 1);
+        assert_eq!(app.do_action(Key::Char('q')).await, AppReturn::Exit);
+    }
+
+    #[tokio::test]
+    async fn should_run_command() {
+        let (mut app, _io_rx) = test_app();
+
+        type_keys(&mut app, ":d 3\n").await;
+        assert!(!app.command_line().is_open());
+        assert_eq!(app.model().delay, Duration::from_secs(3));
+
+        // The unknown commands are notified
+        type_keys(&mut app, ":x\n").await;
+        assert_eq!(app.notifications().history().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_paste_in_text_input_only() {
+        let (mut app, mut io_rx) = test_app();
+
+        // The chars of the actions are ignored
+        assert_eq!(app.do_paste(String::from("sq")).await, AppReturn::Continue);
+        assert!(io_rx.try_recv().is_err());
+
+        app.do_action(Key::Char(':')).await;
+        app.do_paste(String::from("delay 4")).await;
+        app.do_action(Key::Enter).await;
+        assert_eq!(app.model().delay, Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    async fn should_run_action_from_palette() {
+        let (mut app, mut io_rx) = test_app();
+
+        app.do_action(Key::Ctrl('p')).await;
+        assert!(app.palette().is_some());
+        type_keys(&mut app, "sl\n").await;
+        assert!(app.palette().is_none());
+        assert!(matches!(io_rx.try_recv(), Ok(IoRequest::Run(..))));
+    }
+
+    #[tokio::test]
+    async fn should_fail_task_when_queue_is_full() {
+        let (mut app, mut io_rx) = test_app();
+
+        // The dispatch never waits for the IO loop
+        type_keys(&mut app, &"s".repeat(TEST_QUEUE + 1)).await;
+        assert_eq!(app.notifications().history().count(), 1);
+        assert!(matches!(io_rx.try_recv(), Ok(IoRequest::Run(..))));
+
+        app.do_action(Key::Char('s')).await;
+        assert_eq!(app.notifications().history().count(), 1);
     }
 }
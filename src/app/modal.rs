@@ -0,0 +1,188 @@
+use super::input::TextInput;
+use crate::inputs::key::Key;
+
+/// A dialog displayed over the layout, capturing the keys while it's open.
+///
+/// The result of a confirm or of a prompt is delivered back to the model as a message
+/// with the action of the modal, see [`ModalEvent`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modal<A> {
+    pub(crate) title: String,
+    pub(crate) message: String,
+    pub(crate) kind: ModalKind<A>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ModalKind<A> {
+    /// Just dismissed
+    Alert,
+    /// A yes/no question, an exit is confirmed without action
+    Confirm { on_confirm: Option<A>, yes: bool },
+    /// A line of text
+    Prompt { input: TextInput, on_submit: A },
+}
+
+/// The result of a key pressed in a modal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModalEvent<A> {
+    /// The modal is still open
+    Open,
+    /// The modal is dismissed, or the confirm declined
+    Closed,
+    /// The confirm is accepted
+    Action(A),
+    /// The text submitted in a prompt
+    Input(A, String),
+    /// The exit is confirmed
+    Exit,
+}
+
+impl<A: Copy> Modal<A> {
+    /// Display a message, until `<Enter>` or `<Esc>` is pressed
+    pub fn alert(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(title, message, ModalKind::Alert)
+    }
+
+    /// Ask a yes/no question, the action is run if the user answers yes
+    pub fn confirm(title: impl Into<String>, message: impl Into<String>, on_confirm: A) -> Self {
+        let kind = ModalKind::Confirm {
+            on_confirm: Some(on_confirm),
+            yes: false,
+        };
+        Self::new(title, message, kind)
+    }
+
+    /// Ask for a line of text, submitted with the action
+    pub fn prompt(title: impl Into<String>, message: impl Into<String>, on_submit: A) -> Self {
+        let kind = ModalKind::Prompt {
+            input: TextInput::single_line(),
+            on_submit,
+        };
+        Self::new(title, message, kind)
+    }
+
+    /// Ask before exiting while some tasks are running
+    pub(crate) fn confirm_exit(tasks: usize) -> Self {
+        let message = format!("{} task(s) still running, quit anyway?", tasks);
+        let kind = ModalKind::Confirm {
+            on_confirm: None,
+            yes: false,
+        };
+        Self::new("Quit", message, kind)
+    }
+
+    fn new(title: impl Into<String>, message: impl Into<String>, kind: ModalKind<A>) -> Self {
+        let title = title.into();
+        let message = message.into();
+        Self {
+            title,
+            message,
+            kind,
+        }
+    }
+
+    /// Insert a pasted text in the prompt
+    pub fn paste(&mut self, text: &str) {
+        if let ModalKind::Prompt { input, .. } = &mut self.kind {
+            input.paste(text);
+        }
+    }
+
+    /// Handle a key press
+    pub fn on_key(&mut self, key: Key) -> ModalEvent<A> {
+        match &mut self.kind {
+            ModalKind::Alert => match key {
+                Key::Enter | Key::Esc => ModalEvent::Closed,
+                _ => ModalEvent::Open,
+            },
+            ModalKind::Confirm { on_confirm, yes } => match key {
+                Key::Char('y') => confirmed(*on_confirm),
+                Key::Enter if *yes => confirmed(*on_confirm),
+                Key::Char('n') | Key::Esc | Key::Enter => ModalEvent::Closed,
+                Key::Left | Key::Right | Key::Tab | Key::BackTab => {
+                    *yes = !*yes;
+                    ModalEvent::Open
+                }
+                _ => ModalEvent::Open,
+            },
+            ModalKind::Prompt { input, on_submit } => match key {
+                Key::Enter => ModalEvent::Input(*on_submit, input.submit()),
+                Key::Esc => ModalEvent::Closed,
+                key => {
+                    input.on_key(key);
+                    ModalEvent::Open
+                }
+            },
+        }
+    }
+}
+
+impl<A> Modal<A> {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+fn confirmed<A>(on_confirm: Option<A>) -> ModalEvent<A> {
+    match on_confirm {
+        Some(action) => ModalEvent::Action(action),
+        None => ModalEvent::Exit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_dismiss_alert() {
+        let mut modal = Modal::<()>::alert("Oops", "Something went wrong");
+        assert_eq!(modal.on_key(Key::Char('q')), ModalEvent::Open);
+        assert_eq!(modal.on_key(Key::Enter), ModalEvent::Closed);
+    }
+
+    #[test]
+    fn should_confirm() {
+        let mut modal = Modal::confirm("Sleep", "Sleep now?", 42);
+        assert_eq!(modal.on_key(Key::Char('y')), ModalEvent::Action(42));
+
+        // No is selected by default
+        assert_eq!(modal.on_key(Key::Enter), ModalEvent::Closed);
+        assert_eq!(modal.on_key(Key::Tab), ModalEvent::Open);
+        assert_eq!(modal.on_key(Key::Enter), ModalEvent::Action(42));
+    }
+
+    #[test]
+    fn should_confirm_exit() {
+        let mut modal = Modal::<()>::confirm_exit(2);
+        assert_eq!(modal.on_key(Key::Char('n')), ModalEvent::Closed);
+        assert_eq!(modal.on_key(Key::Char('y')), ModalEvent::Exit);
+    }
+
+    #[test]
+    fn should_paste_in_prompt() {
+        let mut modal = Modal::prompt("Delay", "Delay in seconds", 42);
+        modal.paste("12\n");
+        let expected = ModalEvent::Input(42, String::from("12"));
+        assert_eq!(modal.on_key(Key::Enter), expected);
+    }
+
+    #[test]
+    fn should_submit_prompt() {
+        let mut modal = Modal::prompt("Delay", "Delay in seconds", 42);
+        for key in [
+            Key::Char('1'),
+            Key::Char('2'),
+            Key::Backspace,
+            Key::Char('3'),
+        ] {
+            assert_eq!(modal.on_key(key), ModalEvent::Open);
+        }
+        let expected = ModalEvent::Input(42, String::from("13"));
+        assert_eq!(modal.on_key(Key::Enter), expected);
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::{Debug, Display};
+
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::Frame;
+
+use super::actions::Action;
+use super::command::{Command, CommandSpec};
+use super::modal::Modal;
+use super::notifications::Severity;
+use crate::inputs::key::Key;
+use crate::io::{IoError, TaskId};
+
+/// The messages received by a [`Model`]
+#[derive(Debug)]
+pub enum Msg<A, O, E> {
+    /// Sent once, before the first frame
+    Init,
+    /// An action triggered by keys or by the mouse
+    Action(A),
+    /// The text submitted in a prompt, with the action of the prompt
+    Input(A, String),
+    /// A command submitted in the command line
+    Command(Command),
+    /// A key pressed while a text input of the model is focused
+    Key(Key),
+    /// A text pasted while a text input of the model is focused
+    Paste(String),
+    /// Sent at each tick
+    Tick,
+    /// The result of a dispatched event, the failures are already notified
+    Outcome(TaskId, Result<O, IoError<E>>),
+}
+
+/// The messages of a model
+pub type ModelMsg<M> = Msg<<M as Model>::Action, <M as Model>::Outcome, <M as Model>::Error>;
+
+/// The commands of a model
+pub type ModelCmd<M> = Cmd<<M as Model>::Event, <M as Model>::Screen, <M as Model>::Action>;
+
+/// The side effects requested by an update, run by the [`App`](super::App)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Cmd<E, S, A> {
+    /// Nothing to do
+    #[default]
+    None,
+    /// Run the commands in order
+    Batch(Vec<Cmd<E, S, A>>),
+    /// Run an IO event as a new task
+    Dispatch(E),
+    /// Cancel the last dispatched task still queued or running
+    CancelLastTask,
+    /// Display a toast to the user
+    Notify(Severity, String),
+    /// Show or hide the error history
+    ToggleErrors,
+    /// Draw a new frame, e.g. for an animation updated by [`Msg::Tick`]
+    Redraw,
+    /// Display a new screen
+    Push(S),
+    /// Go back to the previous screen
+    Pop,
+    /// Replace the current screen
+    Replace(S),
+    /// Open a modal over the current screen
+    Modal(Modal<A>),
+    /// Give the keys back to the focused text input, after `Esc` has released them
+    Focus,
+    /// Suspend the application
+    Suspend,
+    /// Exit the application
+    Exit,
+}
+
+impl<E, S, A> Cmd<E, S, A> {
+    /// Display a toast to the user
+    pub fn notify(severity: Severity, message: impl Into<String>) -> Self {
+        Self::Notify(severity, message.into())
+    }
+
+    /// Run this command, then the other one
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::None, cmd) | (cmd, Self::None) => cmd,
+            (Self::Batch(mut cmds), cmd) => {
+                cmds.push(cmd);
+                Self::Batch(cmds)
+            }
+            (cmd, other) => Self::Batch(vec![cmd, other]),
+        }
+    }
+
+    /// The commands to run in order, without batch nor `None`
+    pub fn flatten(self) -> Vec<Self> {
+        let mut result = Vec::new();
+        self.flatten_into(&mut result);
+        result
+    }
+
+    fn flatten_into(self, result: &mut Vec<Self>) {
+        match self {
+            Self::None => {}
+            Self::Batch(cmds) => cmds.into_iter().for_each(|cmd| cmd.flatten_into(result)),
+            cmd => result.push(cmd),
+        }
+    }
+}
+
+/// The built-in panels displayed around the view of a screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Panels {
+    /// The running and finished tasks, below the view
+    pub tasks: bool,
+    /// The keys of the contextual actions, on the right of the view
+    pub help: bool,
+    /// The logs, at the bottom
+    pub logs: bool,
+}
+
+impl Default for Panels {
+    fn default() -> Self {
+        Self {
+            tasks: true,
+            help: true,
+            logs: true,
+        }
+    }
+}
+
+/// The state of an application, updated by messages and drawn by a view.
+///
+/// The application is a stack of screens, each screen has its own title, actions,
+/// update and view. The `Esc` key goes back to the previous screen, if it's not bound.
+///
+/// The `update` is pure: it builds the next state and the [`Cmd`] to run,
+/// so the transitions could be tested without a terminal nor a runtime.
+/// The [`App`](super::App) handles the keys, the mouse, the tasks, the notifications and the
+/// layout, the model only draws its own body
+pub trait Model: Sized {
+    /// The actions bound to keys
+    type Action: Action;
+    /// The events run by the [`IoHandler`](crate::io::IoHandler)
+    type Event: Debug + Display + Clone;
+    /// The successful outcome of an event
+    type Outcome;
+    /// The failure of an event
+    type Error: Display;
+    /// The screens, the default one is the root screen
+    type Screen: Debug + Clone + Default;
+
+    /// The title of a screen
+    fn title(&self, screen: &Self::Screen) -> String;
+
+    /// The contextual actions of a screen, checked again after each update
+    fn actions(&self, screen: &Self::Screen) -> Vec<Self::Action>;
+
+    /// The commands of the command line of a screen, opened by `:` if there is any command
+    fn commands(&self, _screen: &Self::Screen) -> Vec<CommandSpec> {
+        Vec::new()
+    }
+
+    /// Whether a text input of the screen is focused,
+    /// the keys are then given to the model as [`Msg::Key`] instead of running actions.
+    /// An `Esc` which leaves the screen focused releases the focus until the model unfocuses
+    fn is_focused(&self, _screen: &Self::Screen) -> bool {
+        false
+    }
+
+    /// The built-in panels of a screen, the view gets the remaining space
+    fn panels(&self, _screen: &Self::Screen) -> Panels {
+        Panels::default()
+    }
+
+    /// Build the next state, and the side effects to run, on the current screen
+    fn update(self, screen: &Self::Screen, msg: ModelMsg<Self>) -> (Self, ModelCmd<Self>);
+
+    /// Draw the body of a screen, in the space left by its panels
+    fn view<B: Backend>(&self, screen: &Self::Screen, rect: &mut Frame<B>, area: Rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{TestAction, TestEvent, TestModel, TestScreen};
+
+    #[test]
+    fn should_flatten_commands() {
+        let cmd = Cmd::<_, (), ()>::Dispatch(1)
+            .and(Cmd::None)
+            .and(Cmd::Batch(vec![Cmd::ToggleErrors, Cmd::None]))
+            .and(Cmd::Exit);
+        let expected = vec![Cmd::Dispatch(1), Cmd::ToggleErrors, Cmd::Exit];
+        assert_eq!(cmd.flatten(), expected);
+    }
+
+    #[test]
+    fn should_update_without_runtime() {
+        let model = TestModel::default();
+        let (model, cmd) = model.update(&TestScreen::Main, Msg::Action(TestAction::IncrementDelay));
+        assert_eq!(cmd, Cmd::None);
+
+        let (model, cmd) = model.update(&TestScreen::Main, Msg::Action(TestAction::Sleep));
+        let delay = Duration::from_secs(1);
+        assert_eq!(cmd, Cmd::Dispatch(TestEvent::Sleep(delay)));
+
+        let (model, cmd) = model.update(&TestScreen::Main, Msg::Outcome(TaskId(1), Ok(delay)));
+        assert_eq!(cmd, Cmd::None);
+        assert_eq!(model.sleeps, 1);
+
+        let (_, cmd) = model.update(&TestScreen::Main, Msg::Action(TestAction::Quit));
+        assert_eq!(cmd, Cmd::Exit);
+    }
+}
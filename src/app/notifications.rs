@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// Delay before a toast is dismissed
+const TOAST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of toasts displayed at the same time
+const MAX_TOASTS: usize = 3;
+/// Number of errors kept in the history
+const HISTORY_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// A message for the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    pub created: Instant,
+}
+
+/// The toasts currently displayed, and the history of the errors
+#[derive(Debug, Default, Clone)]
+pub struct Notifications {
+    toasts: VecDeque<Notification>,
+    history: VecDeque<Notification>,
+}
+
+impl Notifications {
+    /// Display a toast, warnings and errors are kept in the history
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        let notification = Notification {
+            severity,
+            message: message.into(),
+            created: Instant::now(),
+        };
+        if severity >= Severity::Warning {
+            self.history.push_back(notification.clone());
+            if self.history.len() > HISTORY_SIZE {
+                self.history.pop_front();
+            }
+        }
+        self.toasts.push_back(notification);
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Dismiss the expired toasts, returns `true` if some toasts are dismissed
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.toasts.len();
+        self.toasts
+            .retain(|toast| now.duration_since(toast.created) < TOAST_TIMEOUT);
+        count != self.toasts.len()
+    }
+
+    /// The displayed toasts, from the oldest to the newest
+    pub fn toasts(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.toasts.iter()
+    }
+
+    /// The warnings and errors, from the oldest to the newest
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.history.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_dismiss_toasts_and_keep_errors() {
+        let mut notifications = Notifications::default();
+        notifications.push(Severity::Info, "Hello");
+        notifications.push(Severity::Error, "Oops");
+        assert_eq!(notifications.toasts().count(), 2);
+        assert_eq!(notifications.history().count(), 1);
+
+        assert!(!notifications.expire(Instant::now()));
+        assert!(notifications.expire(Instant::now() + TOAST_TIMEOUT));
+        assert_eq!(notifications.toasts().count(), 0);
+        assert_eq!(notifications.history().count(), 1);
+    }
+
+    #[test]
+    fn should_limit_toasts() {
+        let mut notifications = Notifications::default();
+        for i in 0..MAX_TOASTS + 2 {
+            notifications.push(Severity::Warning, i.to_string());
+        }
+        let toasts: Vec<_> = notifications.toasts().map(|t| t.message.as_str()).collect();
+        assert_eq!(toasts, vec!["2", "3", "4"]);
+    }
+}
@@ -0,0 +1,206 @@
+use super::actions::Action;
+use super::input::TextInput;
+use crate::inputs::key::Key;
+
+/// Bonus of a matched char following the previous matched one
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Bonus of a matched char at the beginning of a word
+const WORD_START_BONUS: i64 = 3;
+
+/// An action matching the query of the palette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteMatch<A> {
+    pub action: A,
+    /// The matched chars of the action name
+    pub positions: Vec<usize>,
+    /// Whether the action is contextual, the other ones cannot be run
+    pub available: bool,
+    score: i64,
+}
+
+/// The result of a key pressed in the palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteEvent<A> {
+    /// The palette is still open
+    Open,
+    /// The palette is closed without action
+    Closed,
+    /// Run the selected action
+    Run(A),
+}
+
+/// A command palette, fuzzy searching every action by its name
+#[derive(Debug, Clone)]
+pub struct Palette<A: Action> {
+    query: TextInput,
+    /// The contextual actions
+    available: Vec<A>,
+    matches: Vec<PaletteMatch<A>>,
+    selected: usize,
+}
+
+impl<A: Action> Palette<A> {
+    pub fn new(available: &[A]) -> Self {
+        let mut palette = Self {
+            query: TextInput::single_line(),
+            available: available.to_vec(),
+            matches: Vec::new(),
+            selected: 0,
+        };
+        palette.search();
+        palette
+    }
+
+    /// Handle a key press
+    pub fn on_key(&mut self, key: Key) -> PaletteEvent<A> {
+        match key {
+            Key::Esc | Key::Ctrl('c') => return PaletteEvent::Closed,
+            Key::Enter => {
+                return match self.selected() {
+                    Some(selected) => PaletteEvent::Run(selected.action),
+                    None => PaletteEvent::Closed,
+                }
+            }
+            Key::Up | Key::Ctrl('p') | Key::BackTab => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Key::Down | Key::Ctrl('n') | Key::Tab => {
+                self.selected = (self.selected + 1).min(self.matches.len().saturating_sub(1));
+            }
+            key => {
+                if self.query.on_key(key) {
+                    self.search();
+                }
+            }
+        }
+        PaletteEvent::Open
+    }
+
+    /// Insert a pasted text in the query
+    pub fn paste(&mut self, text: &str) {
+        self.query.paste(text);
+        self.search();
+    }
+
+    /// The actions matching the query, the contextual and best ones first
+    fn search(&mut self) {
+        let query = self.query.value();
+        let mut matches: Vec<_> = A::all()
+            .iter()
+            .filter_map(|action| {
+                let (score, positions) = fuzzy_match(&query, &action.to_string())?;
+                let available = self.available.contains(action);
+                Some(PaletteMatch {
+                    action: *action,
+                    positions,
+                    available,
+                    score,
+                })
+            })
+            .collect();
+        // The sort is stable, so the actions keep their order for the same score
+        matches.sort_by_key(|m| (!m.available, -m.score));
+        self.matches = matches;
+        self.selected = 0;
+    }
+
+    pub fn query(&self) -> &TextInput {
+        &self.query
+    }
+
+    pub fn matches(&self) -> &[PaletteMatch<A>] {
+        &self.matches
+    }
+
+    /// The index of the selected match
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&PaletteMatch<A>> {
+        self.matches.get(self.selected)
+    }
+}
+
+/// Match the chars of the query in order, ignoring the case and the spaces.
+/// The matched chars at the beginning of a word, or following each other, are preferred.
+///
+/// Returns the score, higher is better, and the positions of the matched chars
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut chars = text.chars().enumerate().peekable();
+    let mut previous: Option<(usize, char)> = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let (index, c) = loop {
+            let (index, c) = chars.next()?;
+            if c.to_lowercase().eq(q.to_lowercase()) {
+                break (index, c);
+            }
+            previous = Some((index, c));
+        };
+        score += 1;
+        match previous {
+            Some((previous, _)) if positions.last() == Some(&previous) => {
+                score += CONSECUTIVE_BONUS;
+            }
+            Some((_, p)) if !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()) => {
+                score += WORD_START_BONUS;
+            }
+            None => score += WORD_START_BONUS,
+            _ => {}
+        }
+        positions.push(index);
+        previous = Some((index, c));
+    }
+    // Prefer the shortest names
+    score -= (text.chars().count() - positions.len()) as i64 / 4;
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestAction;
+
+    #[test]
+    fn should_fuzzy_match() {
+        let (_, positions) = fuzzy_match("idel", "IncrementDelay").unwrap();
+        assert_eq!(positions, vec![0, 9, 10, 11]);
+        assert_eq!(fuzzy_match("dx", "IncrementDelay"), None);
+        assert_eq!(fuzzy_match("", "Quit"), Some((-1, vec![])));
+    }
+
+    #[test]
+    fn should_prefer_consecutive_and_word_start() {
+        let (consecutive, _) = fuzzy_match("del", "Delete all").unwrap();
+        let (scattered, _) = fuzzy_match("del", "Daily event log").unwrap();
+        assert!(consecutive > scattered);
+
+        let (start, _) = fuzzy_match("d", "Decrement delay").unwrap();
+        let (middle, _) = fuzzy_match("d", "Sleep and wake").unwrap();
+        assert!(start > middle);
+    }
+
+    #[test]
+    fn should_run_selected_action() {
+        let mut palette = Palette::new(&[TestAction::Quit, TestAction::IncrementDelay]);
+        assert_eq!(palette.matches().len(), TestAction::all().len());
+        // The contextual actions first
+        assert!(palette.matches()[1].available);
+        assert!(!palette.matches()[2].available);
+
+        for c in "delay".chars() {
+            palette.on_key(Key::Char(c));
+        }
+        let actions: Vec<_> = palette.matches().iter().map(|m| m.action).collect();
+        assert_eq!(
+            actions,
+            vec![TestAction::IncrementDelay, TestAction::DecrementDelay]
+        );
+        palette.on_key(Key::Down);
+        let expected = PaletteEvent::Run(TestAction::DecrementDelay);
+        assert_eq!(palette.on_key(Key::Enter), expected);
+    }
+}
@@ -0,0 +1,98 @@
+/// The stack of screens, the last pushed one is displayed.
+///
+/// The root screen is never popped, so there is always a current screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screens<S> {
+    stack: Vec<S>,
+}
+
+impl<S: Default> Default for Screens<S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S> Screens<S> {
+    pub fn new(root: S) -> Self {
+        let stack = vec![root];
+        Self { stack }
+    }
+
+    /// The displayed screen
+    pub fn current(&self) -> &S {
+        self.stack.last().expect("the root screen is never popped")
+    }
+
+    /// Display a new screen, the current one is displayed back once it's popped
+    pub fn push(&mut self, screen: S) {
+        self.stack.push(screen);
+    }
+
+    /// Go back to the previous screen, the root screen is kept
+    pub fn pop(&mut self) -> Option<S> {
+        if self.can_pop() {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Replace the current screen, without changing the depth
+    pub fn replace(&mut self, screen: S) -> S {
+        let current = self
+            .stack
+            .last_mut()
+            .expect("the root screen is never popped");
+        std::mem::replace(current, screen)
+    }
+
+    /// Whether there is a previous screen
+    pub fn can_pop(&self) -> bool {
+        self.stack.len() > 1
+    }
+
+    /// The number of screens, the root one included
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// The screens from the root to the current one
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &S> {
+        self.stack.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_push_and_pop() {
+        let mut screens = Screens::new("list");
+        assert!(!screens.can_pop());
+
+        screens.push("detail");
+        screens.push("edit");
+        assert_eq!(screens.current(), &"edit");
+        assert_eq!(screens.depth(), 3);
+
+        assert_eq!(screens.pop(), Some("edit"));
+        assert_eq!(screens.pop(), Some("detail"));
+        assert_eq!(screens.current(), &"list");
+    }
+
+    #[test]
+    fn should_keep_root() {
+        let mut screens = Screens::new("list");
+        assert_eq!(screens.pop(), None);
+        assert_eq!(screens.current(), &"list");
+    }
+
+    #[test]
+    fn should_replace_current() {
+        let mut screens = Screens::new("list");
+        screens.push("detail");
+        assert_eq!(screens.replace("edit"), "detail");
+        assert_eq!(screens.iter().collect::<Vec<_>>(), vec![&"list", &"edit"]);
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::{Duration, Instant};
+
+use crate::io::progress::Progress;
+use crate::io::{Retry, TaskId, TaskStatus};
+
+/// Number of finished tasks kept to be displayed
+const HISTORY_SIZE: usize = 20;
+
+/// An IO task, as known by the App
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task<E> {
+    pub id: TaskId,
+    pub event: E,
+    pub status: TaskStatus,
+    /// When the task has started running
+    pub started: Option<Instant>,
+    /// The last progress reported while running
+    pub progress: Option<Progress>,
+    /// The last retry scheduled after a failed attempt
+    pub retry: Option<Retry>,
+}
+
+impl<E> Task<E> {
+    /// Time spent running, if the task is running
+    pub fn elapsed(&self) -> Option<Duration> {
+        match self.status {
+            TaskStatus::Running => self.started.map(|started| started.elapsed()),
+            _ => None,
+        }
+    }
+}
+
+/// The dispatched IO tasks, the active ones and the last finished ones
+#[derive(Debug, Clone)]
+pub struct Tasks<E> {
+    next_id: u64,
+    tasks: Vec<Task<E>>,
+}
+
+impl<E> Default for Tasks<E> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            tasks: Vec::new(),
+        }
+    }
+}
+
+impl<E> Tasks<E> {
+    /// Register a new queued task
+    pub fn push(&mut self, event: E) -> TaskId {
+        self.next_id += 1;
+        let id = TaskId(self.next_id);
+        let status = TaskStatus::Queued;
+        let started = None;
+        let progress = None;
+        let retry = None;
+        self.tasks.push(Task {
+            id,
+            event,
+            status,
+            started,
+            progress,
+            retry,
+        });
+        self.forget_finished();
+        id
+    }
+
+    /// Update the status of a task, a finished task is never updated again
+    pub fn update(&mut self, id: TaskId, status: TaskStatus) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            if task.status.is_active() {
+                if status == TaskStatus::Running && task.started.is_none() {
+                    task.started = Some(Instant::now());
+                }
+                task.status = status;
+            }
+        }
+        self.forget_finished();
+    }
+
+    /// Update the progress of a running task
+    pub fn progress(&mut self, id: TaskId, progress: Progress) {
+        let task = self.tasks.iter_mut().find(|task| task.id == id);
+        if let Some(task) = task.filter(|task| task.status == TaskStatus::Running) {
+            task.progress = Some(progress);
+        }
+    }
+
+    /// A running task will retry, its progress restarts
+    pub fn retry(&mut self, id: TaskId, retry: Retry) {
+        let task = self.tasks.iter_mut().find(|task| task.id == id);
+        if let Some(task) = task.filter(|task| task.status == TaskStatus::Running) {
+            task.progress = None;
+            task.retry = Some(retry);
+        }
+    }
+
+    /// The last dispatched task still queued or running
+    pub fn last_active(&self) -> Option<&Task<E>> {
+        self.tasks.iter().rev().find(|task| task.status.is_active())
+    }
+
+    /// Number of tasks with the given status
+    pub fn count(&self, status: TaskStatus) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == status)
+            .count()
+    }
+
+    /// The running tasks, from the oldest to the newest
+    pub fn running(&self) -> impl Iterator<Item = &Task<E>> {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Running)
+    }
+
+    /// Some tasks are queued or running
+    pub fn is_active(&self) -> bool {
+        self.tasks.iter().any(|task| task.status.is_active())
+    }
+
+    /// The tasks, from the oldest to the newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Task<E>> {
+        self.tasks.iter()
+    }
+
+    /// Keep only the last finished tasks
+    fn forget_finished(&mut self) {
+        let finished = self.tasks.iter().filter(|t| !t.status.is_active()).count();
+        let mut excess = finished.saturating_sub(HISTORY_SIZE);
+        self.tasks.retain(|task| {
+            if excess > 0 && !task.status.is_active() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_track_tasks() {
+        let mut tasks = Tasks::default();
+        let first = tasks.push("Initialize");
+        let second = tasks.push("Sleep");
+        assert_ne!(first, second);
+        assert_eq!(tasks.last_active().map(|t| t.id), Some(second));
+        assert_eq!(tasks.count(TaskStatus::Queued), 2);
+
+        tasks.update(first, TaskStatus::Running);
+        assert_eq!(tasks.running().count(), 1);
+        assert!(tasks.running().all(|t| t.elapsed().is_some()));
+        let progress = Progress {
+            ratio: 0.5,
+            message: String::from("Half"),
+            eta: None,
+        };
+        tasks.progress(first, progress.clone());
+        tasks.progress(second, progress.clone());
+        let progresses: Vec<_> = tasks.iter().map(|t| t.progress.as_ref()).collect();
+        assert_eq!(progresses, vec![Some(&progress), None]);
+
+        tasks.update(second, TaskStatus::Cancelled);
+        // A late completion does not override the cancellation
+        tasks.update(second, TaskStatus::Done);
+        assert_eq!(tasks.iter().last().unwrap().status, TaskStatus::Cancelled);
+        assert_eq!(tasks.last_active().map(|t| t.id), Some(first));
+
+        tasks.update(first, TaskStatus::Done);
+        assert!(!tasks.is_active());
+        assert_eq!(tasks.running().count(), 0);
+    }
+
+    #[test]
+    fn should_forget_old_finished_tasks() {
+        let mut tasks = Tasks::default();
+        let active = tasks.push("Initialize");
+        for _ in 0..HISTORY_SIZE + 5 {
+            let id = tasks.push("Initialize");
+            tasks.update(id, TaskStatus::Done);
+        }
+        assert_eq!(tasks.iter().count(), HISTORY_SIZE + 1);
+        assert_eq!(tasks.iter().next().map(|t| t.id), Some(active));
+    }
+}
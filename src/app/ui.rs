@@ -1,150 +1,647 @@
-use std::time::Duration;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
 
 use symbols::line;
 use tui::backend::Backend;
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, BorderType, Borders, Cell, LineGauge, Paragraph, Row, Table};
+use tui::widgets::{
+    Block, BorderType, Borders, Cell, Clear, LineGauge, Paragraph, Row, Table, TableState, Wrap,
+};
 use tui::{symbols, Frame};
 use tui_logger::TuiLoggerWidget;
 
-use super::actions::Actions;
-use super::state::AppState;
+use super::actions::{Action, Actions};
+use super::command::{self, CommandSpec};
+use super::input::TextInput;
+use super::keymap::Keymap;
+use super::modal::{Modal, ModalKind};
+use super::model::Model;
+use super::notifications::{Notification, Notifications, Severity};
+use super::palette::Palette;
+use super::tasks::{Task, Tasks};
 use crate::app::App;
+use crate::frame::FrameStats;
+use crate::inputs::key::Key;
+use crate::inputs::mouse::Mouse;
+use crate::io::progress::Progress;
+use crate::io::TaskStatus;
 
-pub fn draw<B>(rect: &mut Frame<B>, app: &App)
+/// The areas of the widgets, used to handle mouse events
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UiAreas {
+    pub help: Rect,
+    pub logs: Rect,
+}
+
+/// The widget targeted by a mouse event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseTarget {
+    /// A visible row of the help table, between its borders
+    HelpRow(usize),
+    /// Scroll the logs upwards
+    LogsUp,
+    /// Scroll the logs downwards
+    LogsDown,
+}
+
+impl UiAreas {
+    /// Hit-test a mouse event against the drawn widgets
+    pub fn target(&self, mouse: Mouse) -> Option<MouseTarget> {
+        let (x, y) = mouse.position();
+        let position = Rect::new(x, y, 1, 1);
+        let target = match mouse {
+            // Skip the borders of the help table, the overflowing rows are not drawn
+            Mouse::Click(_, y)
+                if self.help.intersects(position)
+                    && y > self.help.y
+                    && y + 1 < self.help.bottom() =>
+            {
+                MouseTarget::HelpRow((y - self.help.y - 1) as usize)
+            }
+            Mouse::ScrollUp(..) if self.logs.intersects(position) => MouseTarget::LogsUp,
+            Mouse::ScrollDown(..) if self.logs.intersects(position) => MouseTarget::LogsDown,
+            _ => return None,
+        };
+        Some(target)
+    }
+}
+
+/// Height of the tasks panel, below the view
+const TASKS_HEIGHT: u16 = 7;
+/// Minimal size to display the title, the body and the tasks
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 16;
+/// Size required to display the help
+const HELP_MIN_WIDTH: u16 = 52;
+/// Size required to display the logs
+const LOGS_MIN_HEIGHT: u16 = 28;
+/// Maximal width of a toast
+const TOAST_WIDTH: u16 = 40;
+/// Maximal width of a modal
+const MODAL_WIDTH: u16 = 50;
+/// Maximal width, and number of results, of the command palette
+const PALETTE_WIDTH: u16 = 60;
+const PALETTE_RESULTS: u16 = 10;
+/// Number of completions displayed above the command line
+const COMPLETIONS: usize = 5;
+
+pub fn draw<B, M>(rect: &mut Frame<B>, app: &App<M>, stats: FrameStats) -> UiAreas
 where
     B: Backend,
+    M: Model,
 {
     let size = rect.size();
-    check_size(&size);
+    if is_too_small(&size) {
+        let too_small = draw_too_small(&size);
+        rect.render_widget(too_small, size);
+        return UiAreas::default();
+    }
+
+    // The screen chooses its panels, the view gets the remaining space
+    let screen = app.screens().current();
+    let panels = app.model().panels(screen);
 
-    // Vertical layout
+    // Vertical layout, the logs are collapsed when the height is too small
+    let logs_height = if panels.logs && size.height >= LOGS_MIN_HEIGHT {
+        12
+    } else {
+        0
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
                 Constraint::Length(3),
-                Constraint::Min(10),
-                Constraint::Length(3),
-                Constraint::Length(12),
+                Constraint::Min(13),
+                Constraint::Length(logs_height),
             ]
             .as_ref(),
         )
         .split(size);
 
     // Title
-    let title = draw_title();
+    let title = draw_title(
+        app.model().title(app.screens().current()),
+        stats,
+        app.pending_keys(),
+    );
     rect.render_widget(title, chunks[0]);
 
-    // Body & Help
+    // Body & Help, the help is collapsed when the width is too small
+    let help_width = if panels.help && size.width >= HELP_MIN_WIDTH {
+        32
+    } else {
+        0
+    };
     let body_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(20), Constraint::Length(32)].as_ref())
+        .constraints([Constraint::Min(20), Constraint::Length(help_width)].as_ref())
         .split(chunks[1]);
 
-    let body = draw_body(app.is_loading(), app.state());
-    rect.render_widget(body, body_chunks[0]);
-
-    let help = draw_help(app.actions());
-    rect.render_widget(help, body_chunks[1]);
+    // Body & Tasks, or the error history
+    if app.show_errors() {
+        let errors = draw_errors(app.notifications());
+        rect.render_widget(errors, body_chunks[0]);
+    } else {
+        draw_main(rect, app, body_chunks[0], panels.tasks);
+    }
 
-    // Duration LineGauge
-    if let Some(duration) = app.state().duration() {
-        let duration_block = draw_duration(duration);
-        rect.render_widget(duration_block, chunks[2]);
+    let commands = app.model().commands(screen);
+    if help_width > 0 {
+        // The built-in keys, if they are not bound
+        let mut builtins = vec![];
+        if app.screens().can_pop() {
+            builtins.push((Key::Esc, "Back"));
+        }
+        builtins.push((Key::Ctrl('p'), "Commands"));
+        if !commands.is_empty() {
+            builtins.push((Key::Char(':'), "Command line"));
+        }
+        let help = draw_help(app.actions(), &builtins);
+        rect.render_widget(help, body_chunks[1]);
     }
 
     // Logs
-    let logs = draw_logs();
-    rect.render_widget(logs, chunks[3]);
+    if logs_height > 0 {
+        let mut logs = draw_logs();
+        logs.state(app.logs_state());
+        rect.render_widget(logs, chunks[2]);
+    }
+
+    // Command line, palette and modal, over the layout
+    if let Some(input) = app.command_line().input() {
+        draw_command_line(rect, input, &commands);
+    }
+    if let Some(palette) = app.palette() {
+        draw_palette(rect, palette, app.keymap());
+    }
+    if let Some(modal) = app.modal() {
+        draw_modal(rect, modal);
+    }
+
+    // Toasts, over the other widgets
+    draw_toasts(rect, app.notifications());
+
+    UiAreas {
+        help: body_chunks[1],
+        logs: chunks[2],
+    }
+}
+
+/// The body of the model, and the tasks with their progress if the screen shows them
+fn draw_main<B, M>(rect: &mut Frame<B>, app: &App<M>, area: Rect, show_tasks: bool)
+where
+    B: Backend,
+    M: Model,
+{
+    let screen = app.screens().current();
+    if !show_tasks {
+        app.model().view(screen, rect, area);
+        return;
+    }
+
+    // Body & Tasks
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(TASKS_HEIGHT)].as_ref())
+        .split(area);
+
+    app.model().view(screen, rect, main_chunks[0]);
+
+    // Tasks, with the progress of the running ones above the list
+    let tasks = app.tasks();
+    let title = if tasks.is_active() {
+        format!(
+            "Tasks: {} running, {} queued",
+            tasks.count(TaskStatus::Running),
+            tasks.count(TaskStatus::Queued)
+        )
+    } else {
+        String::from("Tasks")
+    };
+    let tasks_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Plain)
+        .title(title);
+    let tasks_area = tasks_block.inner(main_chunks[1]);
+    rect.render_widget(tasks_block, main_chunks[1]);
+
+    let progresses: Vec<_> = app
+        .tasks()
+        .running()
+        .filter_map(|task| task.progress.as_ref().map(|progress| (task, progress)))
+        .take(tasks_area.height as usize / 2)
+        .collect();
+    let mut constraints = vec![Constraint::Length(1); progresses.len()];
+    constraints.push(Constraint::Min(0));
+    let tasks_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(tasks_area);
+    for ((task, progress), area) in progresses.iter().zip(tasks_chunks.iter()) {
+        let gauge = draw_progress(task, progress);
+        rect.render_widget(gauge, *area);
+    }
+
+    let tasks = draw_tasks(app.tasks());
+    rect.render_widget(tasks, tasks_chunks[progresses.len()]);
 }
 
-fn draw_title<'a>() -> Paragraph<'a> {
-    Paragraph::new("Plop with TUI")
+/// The title, with the frame measures and the partial key sequence on the border,
+/// the title is always shown, even without the help
+fn draw_title<'a>(title: String, stats: FrameStats, pending: &[Key]) -> Paragraph<'a> {
+    let frames = format!(
+        "{} fps, {:.1}ms",
+        stats.fps,
+        stats.frame_time.as_secs_f64() * 1000.0
+    );
+    let mut spans = vec![Span::styled(frames, Style::default().fg(Color::Gray))];
+    if !pending.is_empty() {
+        let keys = pending
+            .iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let keys = format!(" {} … ", keys);
+        spans.push(Span::styled(keys, Style::default().fg(Color::Yellow)));
+    }
+    Paragraph::new(title)
         .style(Style::default().fg(Color::LightCyan))
         .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::White))
-                .border_type(BorderType::Plain),
+                .border_type(BorderType::Plain)
+                .title(Spans::from(spans)),
         )
 }
 
-fn check_size(rect: &Rect) {
-    if rect.width < 52 {
-        panic!("Require width >= 52, (got {})", rect.width);
+fn is_too_small(rect: &Rect) -> bool {
+    rect.width < MIN_WIDTH || rect.height < MIN_HEIGHT
+}
+
+fn draw_too_small<'a>(rect: &Rect) -> Paragraph<'a> {
+    Paragraph::new(vec![
+        Spans::from(Span::raw("Terminal too small")),
+        Spans::from(Span::raw(format!("{}x{}", rect.width, rect.height))),
+        Spans::from(Span::raw(format!("require {}x{}", MIN_WIDTH, MIN_HEIGHT))),
+    ])
+    .style(Style::default().fg(Color::LightRed))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true })
+}
+
+fn draw_tasks<E: Display>(tasks: &Tasks<E>) -> Table<'_> {
+    // The newest tasks first
+    let rows = tasks.iter().rev().map(|task| {
+        let status_style = match task.status {
+            TaskStatus::Queued => Style::default().fg(Color::Gray),
+            TaskStatus::Running => Style::default().fg(Color::LightCyan),
+            TaskStatus::Done => Style::default().fg(Color::Green),
+            TaskStatus::Failed => Style::default().fg(Color::Red),
+            TaskStatus::Cancelled => Style::default().fg(Color::Yellow),
+        };
+        // The running tasks show a spinner and their elapsed time
+        let status = match task.elapsed() {
+            Some(elapsed) => format!(
+                "{} {} {:.1}s",
+                spinner(elapsed),
+                task.status,
+                elapsed.as_secs_f64()
+            ),
+            None => task.status.to_string(),
+        };
+        let status = match &task.retry {
+            Some(retry) if task.status.is_active() => {
+                format!("{} (retry {}/{})", status, retry.retry, retry.max_retries)
+            }
+            _ => status,
+        };
+        Row::new(vec![
+            Cell::from(task.id.to_string()),
+            Cell::from(task.event.to_string()),
+            Cell::from(Span::styled(status, status_style)),
+        ])
+    });
+
+    Table::new(rows)
+        .widths(&[
+            Constraint::Length(4),
+            Constraint::Length(12),
+            Constraint::Min(9),
+        ])
+        .column_spacing(1)
+}
+
+fn draw_progress<'a, E>(task: &Task<E>, progress: &'a Progress) -> LineGauge<'a> {
+    let mut label = format!("{} {}", task.id, progress.message);
+    if let Some(eta) = progress.eta {
+        label.push_str(&format!(", {:.1}s left", eta.as_secs_f64()));
+    }
+    LineGauge::default()
+        .gauge_style(Style::default().fg(Color::LightCyan))
+        .line_set(line::NORMAL)
+        .label(label)
+        .ratio(progress.ratio)
+}
+
+/// The newest toasts at the top right corner
+fn draw_toasts<B>(rect: &mut Frame<B>, notifications: &Notifications)
+where
+    B: Backend,
+{
+    let size = rect.size();
+    let width = (size.width / 2)
+        .clamp(MIN_WIDTH, TOAST_WIDTH)
+        .min(size.width);
+    let mut y = size.y;
+    for toast in notifications.toasts().rev() {
+        if y + 3 > size.bottom() {
+            break;
+        }
+        let area = Rect::new(size.right() - width, y, width, 3);
+        rect.render_widget(Clear, area);
+        rect.render_widget(draw_toast(toast), area);
+        y += 3;
+    }
+}
+
+/// The command line on the last row, with the completions of the command name above
+fn draw_command_line<B>(rect: &mut Frame<B>, input: &TextInput, commands: &[CommandSpec])
+where
+    B: Backend,
+{
+    let size = rect.size();
+    let area = Rect::new(size.x, size.bottom() - 1, size.width, 1);
+    rect.render_widget(Clear, area);
+    draw_input_line(rect, area, ":", input);
+
+    // The arguments are not completed
+    let input = input.value();
+    if input.contains(' ') {
+        return;
     }
-    if rect.height < 28 {
-        panic!("Require height >= 28, (got {})", rect.height);
+    let completions = command::completions(&input, commands);
+    let height = completions.len().min(COMPLETIONS) as u16;
+    if height == 0 || height >= size.height {
+        return;
     }
+    let area = Rect::new(size.x, area.y - height, MODAL_WIDTH.min(size.width), height);
+    let rows = completions.iter().map(|command| {
+        Row::new(vec![
+            Cell::from(Span::styled(
+                command.name,
+                Style::default().fg(Color::LightCyan),
+            )),
+            Cell::from(command.args),
+            Cell::from(Span::styled(command.help, Style::default().fg(Color::Gray))),
+        ])
+    });
+    let table = Table::new(rows)
+        .style(Style::default().bg(Color::Black))
+        .widths(&[
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Min(10),
+        ])
+        .column_spacing(1);
+    rect.render_widget(Clear, area);
+    rect.render_widget(table, area);
 }
 
-fn draw_body<'a>(loading: bool, state: &AppState) -> Paragraph<'a> {
-    let initialized_text = if state.is_initialized() {
-        "Initialized"
-    } else {
-        "Not Initialized !"
-    };
-    let loading_text = if loading { "Loading..." } else { "" };
-    let sleep_text = if let Some(sleeps) = state.count_sleep() {
-        format!("Sleep count: {}", sleeps)
-    } else {
-        String::default()
+/// A focused text input on one line, after a prefix, e.g. `> `
+fn draw_input_line<B>(rect: &mut Frame<B>, area: Rect, prefix: &str, input: &TextInput)
+where
+    B: Backend,
+{
+    let width = (prefix.chars().count() as u16).min(area.width);
+    let prefix_area = Rect { width, ..area };
+    let input_area = Rect {
+        x: area.x + width,
+        width: area.width - width,
+        ..area
     };
-    let tick_text = if let Some(ticks) = state.count_tick() {
-        format!("Tick count: {}", ticks)
-    } else {
-        String::default()
+    let prefix = Span::styled(prefix, Style::default().fg(Color::LightCyan));
+    rect.render_widget(Paragraph::new(prefix), prefix_area);
+    input.render(rect, input_area, None, true);
+}
+
+/// The query of the palette, and the matching actions with their keys
+fn draw_palette<B, A>(rect: &mut Frame<B>, palette: &Palette<A>, keymap: &Keymap<A>)
+where
+    B: Backend,
+    A: Action,
+{
+    let size = rect.size();
+    let width = PALETTE_WIDTH.min(size.width);
+    // The borders, the query, and the results
+    let results = (palette.matches().len() as u16).min(PALETTE_RESULTS);
+    let height = (results + 3).min(size.height);
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 4,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .title("Commands");
+    let inner = block.inner(area);
+    rect.render_widget(Clear, area);
+    rect.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(inner);
+    draw_input_line(rect, chunks[0], "> ", palette.query());
+    let key_style = Style::default().fg(Color::LightCyan);
+
+    // The matched chars are highlighted, the actions not contextual are dimmed
+    let rows = palette.matches().iter().map(|m| {
+        let style = if m.available {
+            Style::default()
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let matched = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let name = m
+            .action
+            .to_string()
+            .chars()
+            .enumerate()
+            .map(|(index, c)| {
+                let style = if m.positions.contains(&index) {
+                    matched
+                } else {
+                    style
+                };
+                Span::styled(c.to_string(), style)
+            })
+            .collect::<Vec<_>>();
+        let keys = keymap
+            .keys(&m.action)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Row::new(vec![
+            Cell::from(Spans::from(name)),
+            Cell::from(Span::styled(keys, key_style)),
+        ])
+    });
+    let table = Table::new(rows)
+        .widths(&[Constraint::Percentage(60), Constraint::Percentage(40)])
+        .column_spacing(1)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    state.select(palette.selected().map(|_| palette.selected_index()));
+    rect.render_stateful_widget(table, chunks[1], &mut state);
+}
+
+/// The modal, centered, with its answer below the message
+fn draw_modal<B, A>(rect: &mut Frame<B>, modal: &Modal<A>)
+where
+    B: Backend,
+{
+    let size = rect.size();
+    let width = MODAL_WIDTH.min(size.width);
+    // The borders, the wrapped message, a blank line and the answer
+    let message_width = width.saturating_sub(2).max(1) as usize;
+    let message_height = (modal.message().chars().count() / message_width + 1) as u16;
+    let height = (message_height + 4).min(size.height);
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .title(modal.title());
+    let inner = block.inner(area);
+    rect.render_widget(Clear, area);
+    rect.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(inner);
+    let message = Paragraph::new(modal.message()).wrap(Wrap { trim: true });
+    rect.render_widget(message, chunks[0]);
+
+    let key_style = Style::default().fg(Color::LightCyan);
+    let (answer, alignment) = match &modal.kind {
+        ModalKind::Alert => {
+            let answer = Spans::from(vec![
+                Span::styled(Key::Enter.to_string(), key_style),
+                Span::raw(" OK"),
+            ]);
+            (answer, Alignment::Center)
+        }
+        ModalKind::Confirm { yes, .. } => {
+            let selected = Style::default().add_modifier(Modifier::REVERSED);
+            let (yes_style, no_style) = if *yes {
+                (selected, Style::default())
+            } else {
+                (Style::default(), selected)
+            };
+            let answer = Spans::from(vec![
+                Span::styled(" Yes ", yes_style),
+                Span::raw("   "),
+                Span::styled(" No ", no_style),
+            ]);
+            (answer, Alignment::Center)
+        }
+        ModalKind::Prompt { input, .. } => {
+            draw_input_line(rect, chunks[1], "> ", input);
+            return;
+        }
     };
-    Paragraph::new(vec![
-        Spans::from(Span::raw(initialized_text)),
-        Spans::from(Span::raw(loading_text)),
-        Spans::from(Span::raw(sleep_text)),
-        Spans::from(Span::raw(tick_text)),
-    ])
-    .style(Style::default().fg(Color::LightCyan))
-    .alignment(Alignment::Left)
-    .block(
-        Block::default()
-            // .title("Body")
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .border_type(BorderType::Plain),
-    )
-}
-
-fn draw_duration(duration: &Duration) -> LineGauge {
-    let sec = duration.as_secs();
-    let label = format!("{}s", sec);
-    let ratio = sec as f64 / 10.0;
-    LineGauge::default()
+    let answer = Paragraph::new(answer).alignment(alignment);
+    rect.render_widget(answer, chunks[1]);
+}
+
+fn draw_toast(toast: &Notification) -> Paragraph<'_> {
+    let color = severity_color(toast.severity);
+    Paragraph::new(toast.message.as_str())
+        .style(Style::default().fg(Color::White))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Sleep duration"),
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(color))
+                .title(Span::styled(
+                    toast.severity.to_string(),
+                    Style::default().fg(color),
+                )),
         )
-        .gauge_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .bg(Color::Black)
-                .add_modifier(Modifier::BOLD),
+}
+
+/// The warnings and errors, the newest first
+fn draw_errors(notifications: &Notifications) -> Table<'_> {
+    let now = Instant::now();
+    let rows = notifications.history().rev().map(|notification| {
+        let age = now.duration_since(notification.created).as_secs();
+        let color = severity_color(notification.severity);
+        Row::new(vec![
+            Cell::from(format!("{}s ago", age)),
+            Cell::from(Span::styled(
+                notification.severity.to_string(),
+                Style::default().fg(color),
+            )),
+            Cell::from(notification.message.as_str()),
+        ])
+    });
+
+    Table::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Error history"),
         )
-        .line_set(line::THICK)
-        .label(label)
-        .ratio(ratio)
+        .widths(&[
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Min(10),
+        ])
+        .column_spacing(1)
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => Color::LightCyan,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
 }
 
-fn draw_help(actions: &Actions) -> Table {
+/// The duration of a spinner frame
+pub const SPINNER_PERIOD: Duration = Duration::from_millis(100);
+
+/// The spinner frame after some elapsed time
+fn spinner(elapsed: Duration) -> &'static str {
+    const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    let frame = (elapsed.as_millis() / SPINNER_PERIOD.as_millis()) as usize % FRAMES.len();
+    FRAMES[frame]
+}
+
+fn draw_help<'a, A: Action>(actions: &'a Actions<A>, builtins: &[(Key, &'a str)]) -> Table<'a> {
     let key_style = Style::default().fg(Color::LightCyan);
     let help_style = Style::default().fg(Color::Gray);
 
     let mut rows = vec![];
     for action in actions.actions().iter() {
         let mut first = true;
-        for key in action.keys() {
+        for key in actions.keys(action) {
             let help = if first {
                 first = false;
                 action.to_string()
@@ -158,6 +655,17 @@ fn draw_help(actions: &Actions) -> Table {
             rows.push(row);
         }
     }
+    // The last rows, so the rows of the actions are kept for the mouse
+    for &(key, help) in builtins {
+        // The actions could be bound to the same keys
+        if actions.find(&[key]).is_none() {
+            let row = Row::new(vec![
+                Cell::from(Span::styled(key.to_string(), key_style)),
+                Cell::from(Span::styled(help, help_style)),
+            ]);
+            rows.push(row);
+        }
+    }
 
     Table::new(rows)
         .block(
@@ -170,6 +678,15 @@ fn draw_help(actions: &Actions) -> Table {
         .column_spacing(1)
 }
 
+/// The action displayed at a row of the help table, there is one row per key sequence
+pub fn help_action<A: Action>(actions: &Actions<A>, row: usize) -> Option<A> {
+    actions
+        .actions()
+        .iter()
+        .flat_map(|action| actions.keys(action).iter().map(move |_| *action))
+        .nth(row)
+}
+
 fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
     TuiLoggerWidget::default()
         .style_error(Style::default().fg(Color::Red))
@@ -185,3 +702,92 @@ fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
         )
         .style(Style::default().fg(Color::White).bg(Color::Black))
 }
+
+#[cfg(test)]
+mod tests {
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    use super::*;
+    use crate::testing::{test_app, test_app_with, TestAction, TestModel};
+
+    #[test]
+    fn should_find_mouse_target() {
+        let areas = UiAreas {
+            help: Rect::new(60, 3, 32, 20),
+            logs: Rect::new(0, 30, 100, 10),
+        };
+        assert_eq!(
+            areas.target(Mouse::Click(70, 4)),
+            Some(MouseTarget::HelpRow(0))
+        );
+        assert_eq!(areas.target(Mouse::Click(70, 3)), None);
+        assert_eq!(
+            areas.target(Mouse::Click(70, 21)),
+            Some(MouseTarget::HelpRow(17))
+        );
+        assert_eq!(areas.target(Mouse::Click(70, 22)), None);
+        assert_eq!(areas.target(Mouse::Click(59, 4)), None);
+        assert_eq!(
+            areas.target(Mouse::ScrollUp(5, 35)),
+            Some(MouseTarget::LogsUp)
+        );
+        assert_eq!(
+            areas.target(Mouse::ScrollDown(99, 39)),
+            Some(MouseTarget::LogsDown)
+        );
+        assert_eq!(areas.target(Mouse::ScrollDown(5, 40)), None);
+        assert_eq!(areas.target(Mouse::Click(5, 35)), None);
+        // A drag hits nothing, even over the help or the logs
+        assert_eq!(areas.target(Mouse::Drag(70, 4)), None);
+        assert_eq!(areas.target(Mouse::Drag(5, 35)), None);
+    }
+
+    #[test]
+    fn should_draw_any_size() {
+        let (mut app, _io_rx) = test_app();
+        app.notify(Severity::Error, "Oops");
+        app.modal = Some(Modal::prompt(
+            "Delay",
+            "Delay in seconds",
+            TestAction::Sleep,
+        ));
+        app.palette = Some(Palette::new(&[TestAction::Sleep]));
+
+        for (width, height) in [(10, 5), (30, 20), (60, 20), (40, 30), (80, 40)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let mut areas = UiAreas::default();
+            terminal
+                .draw(|rect| areas = draw(rect, &app, FrameStats::default()))
+                .unwrap();
+
+            assert_eq!(
+                areas.help.width > 0,
+                width >= HELP_MIN_WIDTH && height >= MIN_HEIGHT
+            );
+            assert_eq!(
+                areas.logs.height > 0,
+                height >= LOGS_MIN_HEIGHT && width >= MIN_WIDTH
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_draw_pending_keys_without_help() {
+        let keymap = r#"sleep = ["<g> <s>"]"#.parse().unwrap();
+        let (mut app, _io_rx) = test_app_with(TestModel::default(), keymap);
+        app.do_action(Key::Char('g')).await;
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+        terminal
+            .draw(|rect| {
+                draw(rect, &app, FrameStats::default());
+            })
+            .unwrap();
+        let title = terminal.backend().buffer().content[..40]
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect::<String>();
+        assert!(title.contains("<g> …"), "{:?}", title);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// Schedule the frames at a fixed rate, and measure the drawn frames
+pub struct FrameScheduler {
+    interval: Interval,
+    /// Instants of the frames drawn during the last second
+    frames: VecDeque<Instant>,
+    frame_time: Duration,
+}
+
+/// Measures of the drawn frames
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Frames drawn during the last second
+    pub fps: usize,
+    /// Duration of the last frame
+    pub frame_time: Duration,
+}
+
+impl FrameScheduler {
+    /// Schedule `frame_rate` frames per second
+    pub fn new(frame_rate: u16) -> Self {
+        let period = Duration::from_secs(1) / u32::from(frame_rate.max(1));
+        let mut interval = tokio::time::interval(period);
+        // Skip the frames that could not be drawn in time
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        Self {
+            interval,
+            frames: VecDeque::new(),
+            frame_time: Duration::ZERO,
+        }
+    }
+
+    /// Wait for the next frame
+    pub async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    /// Record a drawn frame, started at the given instant
+    pub fn record(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.frame_time = now - started;
+        self.frames.push_back(now);
+        while let Some(first) = self.frames.front() {
+            if now.duration_since(*first) < Duration::from_secs(1) {
+                break;
+            }
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        FrameStats {
+            fps: self.frames.len(),
+            frame_time: self.frame_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_measure_frames() {
+        let mut scheduler = FrameScheduler::new(60);
+        for _ in 0..3 {
+            scheduler.record(Instant::now());
+        }
+        let stats = scheduler.stats();
+        assert_eq!(stats.fps, 3);
+        assert!(stats.frame_time < Duration::from_secs(1));
+    }
+}
@@ -1,64 +1,216 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::io;
 use std::time::Duration;
 
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::StreamExt;
 use log::error;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
 
 use super::key::Key;
+use super::mouse::Mouse;
 use super::InputEvent;
 
-/// A small event handler that wrap crossterm input and tick event. Each event
-/// type is handled in its own thread and returned to a common `Receiver`
+/// A small event handler that wrap crossterm input, tick and signal events.
+/// Events are read by an async task and returned to a common `Receiver`
 pub struct Events {
-    rx: tokio::sync::mpsc::Receiver<InputEvent>,
-    // Need to be kept around to prevent disposing the sender side.
-    _tx: tokio::sync::mpsc::Sender<InputEvent>,
-    // To stop the loop
-    stop_capture: Arc<AtomicBool>,
+    rx: mpsc::Receiver<InputEvent>,
+    // To pause, resume, or stop the task
+    control_tx: mpsc::Sender<Control>,
+    task: JoinHandle<()>,
+}
+
+/// Commands sent to the input task
+enum Control {
+    /// Stop reading the terminal, the sender is notified once done
+    Pause(oneshot::Sender<()>),
+    Resume,
+    Stop,
 }
 
 impl Events {
     /// Constructs an new instance of `Events` with the default config.
-    pub fn new(tick_rate: Duration) -> Events {
-        let (tx, rx) = tokio::sync::mpsc::channel(100);
-        let stop_capture = Arc::new(AtomicBool::new(false));
-
-        let event_tx = tx.clone();
-        let event_stop_capture = stop_capture.clone();
-        tokio::spawn(async move {
-            loop {
-                // poll for tick rate duration, if no event, sent tick event.
-                if crossterm::event::poll(tick_rate).unwrap() {
-                    if let crossterm::event::Event::Key(key) = crossterm::event::read().unwrap() {
-                        let key = Key::from(key);
-                        if let Err(err) = event_tx.send(InputEvent::Input(key)).await {
-                            error!("Oops!, {}", err);
-                        }
-                    }
+    pub fn new(tick_rate: Duration) -> io::Result<Events> {
+        let (tx, rx) = mpsc::channel(100);
+        let (control_tx, control_rx) = mpsc::channel(1);
+
+        // Listen signals right now, the default handler would kill the application
+        let signals = TerminationSignals::new()?;
+        let task = tokio::spawn(read_events(tx, control_rx, tick_rate, signals));
+
+        Ok(Events {
+            rx,
+            control_tx,
+            task,
+        })
+    }
+
+    /// Stop reading the terminal, wait for the input task to acknowledge.
+    /// The pending events are dropped, the input task could wait for room to send one
+    pub async fn pause(&mut self) {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        let control_tx = self.control_tx.clone();
+        let paused = async move {
+            if control_tx.send(Control::Pause(ack_tx)).await.is_ok() {
+                let _ = ack_rx.await;
+            }
+        };
+        tokio::pin!(paused);
+        loop {
+            tokio::select! {
+                _ = &mut paused => break,
+                event = self.rx.recv() => if event.is_none() {
+                    break;
+                },
+            }
+        }
+    }
+
+    /// Read the terminal again after a pause
+    pub async fn resume(&mut self) {
+        let _ = self.control_tx.send(Control::Resume).await;
+    }
+
+    /// Attempts to read an event.
+    pub async fn next(&mut self) -> InputEvent {
+        self.rx.recv().await.unwrap_or_else(|| {
+            let err = io::Error::new(io::ErrorKind::BrokenPipe, "The input task is stopped");
+            InputEvent::Error(err)
+        })
+    }
+
+    /// Stop the input task, and wait for its completion
+    pub async fn close(self) {
+        let Events {
+            rx,
+            control_tx,
+            task,
+        } = self;
+        // The input task could wait for room to send an event, it stops once the receiver is dropped
+        drop(rx);
+        let _ = control_tx.send(Control::Stop).await;
+        if let Err(err) = task.await {
+            error!("Oops!, {}", err);
+        }
+    }
+}
+
+/// The input task, stopped when the receiver is dropped, on [`Control::Stop`],
+/// or when the terminal cannot be read anymore
+async fn read_events(
+    tx: mpsc::Sender<InputEvent>,
+    mut control_rx: mpsc::Receiver<Control>,
+    tick_rate: Duration,
+    mut signals: TerminationSignals,
+) {
+    let mut reader = Some(EventStream::new());
+    let mut ticks = tokio::time::interval(tick_rate);
+    // No burst of ticks after a pause
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        let event = tokio::select! {
+            control = control_rx.recv() => match control {
+                Some(Control::Pause(ack)) => {
+                    // Dropping the stream stops the crossterm reader thread
+                    reader = None;
+                    let _ = ack.send(());
+                    continue;
                 }
-                if let Err(err) = event_tx.send(InputEvent::Tick).await {
-                    error!("Oops!, {}", err);
+                Some(Control::Resume) => {
+                    reader = Some(EventStream::new());
+                    continue;
                 }
-                if event_stop_capture.load(Ordering::Relaxed) {
+                Some(Control::Stop) | None => break,
+            },
+            _ = ticks.tick() => InputEvent::Tick,
+            signal = signals.recv() => InputEvent::Terminate(signal),
+            event = next_event(&mut reader) => match event {
+                Some(Ok(event)) => match from_event(event) {
+                    Some(event) => event,
+                    None => continue,
+                },
+                // A broken terminal keeps failing, the error is only reported once
+                Some(Err(err)) => {
+                    let _ = tx.send(InputEvent::Error(err)).await;
                     break;
                 }
-            }
-        });
+                // The terminal is closed, the receiver gets an error once the task is stopped
+                None => break,
+            },
+        };
 
-        Events {
-            rx,
-            _tx: tx,
-            stop_capture,
+        if tx.send(event).await.is_err() {
+            // The receiver is dropped
+            break;
         }
     }
+}
 
-    /// Attempts to read an event.
-    pub async fn next(&mut self) -> InputEvent {
-        self.rx.recv().await.unwrap_or(InputEvent::Tick)
+/// Next terminal event, never completes while paused
+async fn next_event(reader: &mut Option<EventStream>) -> Option<io::Result<Event>> {
+    match reader {
+        Some(reader) => reader.next().await,
+        None => futures::future::pending().await,
     }
+}
+
+fn from_event(event: Event) -> Option<InputEvent> {
+    match event {
+        // The releases are only reported with the keyboard enhancement, a key is handled once
+        Event::Key(key) if key.kind == KeyEventKind::Release => None,
+        Event::Key(key) => Some(InputEvent::Input(Key::from(key))),
+        Event::Mouse(mouse) => Mouse::from_event(mouse).map(InputEvent::Mouse),
+        Event::Resize(width, height) => Some(InputEvent::Resize(width, height)),
+        Event::Paste(text) => Some(InputEvent::Paste(text)),
+        Event::FocusGained | Event::FocusLost => None,
+    }
+}
+
+/// Listen `SIGINT`, `SIGTERM`, and `SIGHUP`
+#[cfg(unix)]
+struct TerminationSignals {
+    sigint: tokio::signal::unix::Signal,
+    sigterm: tokio::signal::unix::Signal,
+    sighup: tokio::signal::unix::Signal,
+}
 
-    /// Close
-    pub fn close(&mut self) {
-        self.stop_capture.store(true, Ordering::Relaxed)
+#[cfg(unix)]
+impl TerminationSignals {
+    fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        Ok(Self {
+            sigint: signal(SignalKind::interrupt())?,
+            sigterm: signal(SignalKind::terminate())?,
+            sighup: signal(SignalKind::hangup())?,
+        })
+    }
+
+    async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _ = self.sigint.recv() => "SIGINT",
+            _ = self.sigterm.recv() => "SIGTERM",
+            _ = self.sighup.recv() => "SIGHUP",
+        }
+    }
+}
+
+/// Listen `Ctrl+C`
+#[cfg(not(unix))]
+struct TerminationSignals;
+
+#[cfg(not(unix))]
+impl TerminationSignals {
+    fn new() -> io::Result<Self> {
+        Ok(Self)
+    }
+
+    async fn recv(&mut self) -> &'static str {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => "Ctrl+C",
+            Err(_) => futures::future::pending().await,
+        }
     }
 }
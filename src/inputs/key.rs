@@ -1,14 +1,22 @@
+use std::error::Error;
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
-use crossterm::event;
+use crossterm::event::{self, KeyModifiers};
+use serde::de::{self, Deserialize, Deserializer};
 
 /// Represents an key.
+///
+/// A key with modifiers is built with [`Key::new`], so the common combinations
+/// stay easy to match, e.g. `Key::Ctrl('c')` or `Key::BackTab`.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub enum Key {
     /// Both Enter (or Return) and numpad Enter
     Enter,
     /// Tabulation key
     Tab,
+    /// Shift + Tabulation key
+    BackTab,
     /// Backspace key
     Backspace,
     /// Escape key
@@ -65,10 +73,124 @@ pub enum Key {
     Char(char),
     Ctrl(char),
     Alt(char),
+    /// Any other combination of modifiers, e.g. `<Ctrl+Alt+x>` or `<Ctrl+Left>`
+    Modified(ModifiedKey),
     Unknown,
 }
 
+/// The key code, without modifiers
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum KeyCode {
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+    Left,
+    Right,
+    Up,
+    Down,
+    Ins,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// Function key, from F0 to F12
+    F(u8),
+    Char(char),
+}
+
+/// A key with a combination of modifiers not covered by the other [`Key`] variants,
+/// only built by [`Key::new`] so the same key is never built twice
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub struct ModifiedKey {
+    modifiers: KeyModifiers,
+    code: KeyCode,
+}
+
+impl ModifiedKey {
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+}
+
+/// The character typed with Shift, `None` if Shift has no effect on it
+fn shifted(c: char) -> Option<char> {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        _ if c.is_uppercase() => Some(c),
+        (Some(upper), None) if c.is_lowercase() => Some(upper),
+        _ => None,
+    }
+}
+
 impl Key {
+    /// Build a key from a code and some modifiers
+    ///
+    /// The shift modifier of a character is applied to the character, e.g. `<Shift+a>` is `<A>`,
+    /// and ignored if the character has no case. `Shift+Tab` is a [`Key::BackTab`].
+    pub fn new(modifiers: KeyModifiers, code: KeyCode) -> Key {
+        let (modifiers, code) = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                let c = shifted(c).unwrap_or(c);
+                (modifiers - KeyModifiers::SHIFT, KeyCode::Char(c))
+            }
+            _ => (modifiers, code),
+        };
+        match (modifiers, code) {
+            (_, KeyCode::F(n)) if n > 12 => Key::Unknown,
+            (KeyModifiers::NONE, code) => Key::from(code),
+            (KeyModifiers::SHIFT, KeyCode::Tab) => Key::BackTab,
+            (KeyModifiers::CONTROL, KeyCode::Char(c)) => Key::Ctrl(c),
+            (KeyModifiers::ALT, KeyCode::Char(c)) => Key::Alt(c),
+            (modifiers, code) => Key::Modified(ModifiedKey { modifiers, code }),
+        }
+    }
+
+    /// The modifiers and the code of the key, `None` for [`Key::Unknown`]
+    pub fn decompose(&self) -> Option<(KeyModifiers, KeyCode)> {
+        let code = match *self {
+            Key::Enter => KeyCode::Enter,
+            Key::Tab => KeyCode::Tab,
+            Key::BackTab => return Some((KeyModifiers::SHIFT, KeyCode::Tab)),
+            Key::Backspace => KeyCode::Backspace,
+            Key::Esc => KeyCode::Esc,
+            Key::Left => KeyCode::Left,
+            Key::Right => KeyCode::Right,
+            Key::Up => KeyCode::Up,
+            Key::Down => KeyCode::Down,
+            Key::Ins => KeyCode::Ins,
+            Key::Delete => KeyCode::Delete,
+            Key::Home => KeyCode::Home,
+            Key::End => KeyCode::End,
+            Key::PageUp => KeyCode::PageUp,
+            Key::PageDown => KeyCode::PageDown,
+            Key::F0 => KeyCode::F(0),
+            Key::F1 => KeyCode::F(1),
+            Key::F2 => KeyCode::F(2),
+            Key::F3 => KeyCode::F(3),
+            Key::F4 => KeyCode::F(4),
+            Key::F5 => KeyCode::F(5),
+            Key::F6 => KeyCode::F(6),
+            Key::F7 => KeyCode::F(7),
+            Key::F8 => KeyCode::F(8),
+            Key::F9 => KeyCode::F(9),
+            Key::F10 => KeyCode::F(10),
+            Key::F11 => KeyCode::F(11),
+            Key::F12 => KeyCode::F(12),
+            Key::Char(c) => KeyCode::Char(c),
+            Key::Ctrl(c) => return Some((KeyModifiers::CONTROL, KeyCode::Char(c))),
+            Key::Alt(c) => return Some((KeyModifiers::ALT, KeyCode::Char(c))),
+            Key::Modified(key) => return Some((key.modifiers, key.code)),
+            Key::Unknown => return None,
+        };
+        Some((KeyModifiers::NONE, code))
+    }
+
     /// If exit
     pub fn is_exit(&self) -> bool {
         matches!(self, Key::Ctrl('c') | Key::Char('q') | Key::Esc)
@@ -80,7 +202,7 @@ impl Key {
     ///
     /// # Panics
     ///
-    /// If `n == 0 || n > 12`
+    /// If `n > 12`
     pub fn from_f(n: u8) -> Key {
         match n {
             0 => Key::F0,
@@ -101,100 +223,428 @@ impl Key {
     }
 }
 
-impl Display for Key {
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Ins => Key::Ins,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::F(n) if n <= 12 => Key::from_f(n),
+            KeyCode::F(_) => Key::Unknown,
+            KeyCode::Char(c) => Key::Char(c),
+        }
+    }
+}
+
+impl Display for KeyCode {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match *self {
-            Key::Alt(' ') => write!(f, "<Alt+Space>"),
-            Key::Ctrl(' ') => write!(f, "<Ctrl+Space>"),
-            Key::Char(' ') => write!(f, "<Space>"),
-            Key::Alt(c) => write!(f, "<Alt+{}>", c),
-            Key::Ctrl(c) => write!(f, "<Ctrl+{}>", c),
-            Key::Char(c) => write!(f, "<{}>", c),
-            _ => write!(f, "<{:?}>", self),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            _ => write!(f, "{:?}", self),
         }
     }
 }
 
+/// Modifiers names, in the display order.
+/// Super, Hyper and Meta are only reported by the terminals with the keyboard enhancement
+const MODIFIERS: [(&str, KeyModifiers); 6] = [
+    ("Ctrl+", KeyModifiers::CONTROL),
+    ("Alt+", KeyModifiers::ALT),
+    ("Shift+", KeyModifiers::SHIFT),
+    ("Super+", KeyModifiers::SUPER),
+    ("Hyper+", KeyModifiers::HYPER),
+    ("Meta+", KeyModifiers::META),
+];
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.decompose() {
+            Some((modifiers, code)) => {
+                write!(f, "<")?;
+                for (name, modifier) in MODIFIERS {
+                    if modifiers.contains(modifier) && *self != Key::BackTab {
+                        write!(f, "{}", name)?;
+                    }
+                }
+                match *self {
+                    Key::BackTab => write!(f, "BackTab>"),
+                    _ => write!(f, "{}>", code),
+                }
+            }
+            None => write!(f, "<{:?}>", self),
+        }
+    }
+}
+
+/// Error returned when a key cannot be parsed from its text representation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseKeyError {
+    /// The key is not surrounded by `<` and `>`
+    MissingBrackets(String),
+    /// The modifiers are not followed by a key, e.g. `<Ctrl+>`
+    MissingKey(String),
+    /// Not a character nor a named key
+    UnknownKey(String),
+    /// Shift has no effect on the character, e.g. `<Shift+1>` is typed as `<!>`
+    ShiftedChar(String),
+    /// A key sequence without any key, e.g. `""`
+    Empty,
+}
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseKeyError::MissingBrackets(s) => {
+                write!(
+                    f,
+                    "Invalid key {:?}, expected a key like <q> or <Ctrl+c>",
+                    s
+                )
+            }
+            ParseKeyError::MissingKey(s) => {
+                write!(f, "Invalid key {:?}, expected a key after the modifiers", s)
+            }
+            ParseKeyError::UnknownKey(s) => write!(f, "Unknown key {:?}", s),
+            ParseKeyError::ShiftedChar(s) => {
+                write!(f, "Invalid key {:?}, use the shifted character instead", s)
+            }
+            ParseKeyError::Empty => write!(f, "Empty key sequence, expected a key like <q>"),
+        }
+    }
+}
+
+impl Error for ParseKeyError {}
+
+/// Parse a character, `Space` is used for `' '`
+fn parse_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ if text == "Space" => Some(' '),
+        _ => None,
+    }
+}
+
+/// Parse a function key, from `F0` to `F12`
+fn parse_function_key(text: &str) -> Option<KeyCode> {
+    let n = text.strip_prefix('F')?;
+    if n.is_empty() || !n.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match n.parse::<u8>() {
+        Ok(n) if n <= 12 => Some(KeyCode::F(n)),
+        _ => None,
+    }
+}
+
+/// Parse a key code, e.g. `q`, `Space`, `Left`, `F5`
+fn parse_code(text: &str) -> Option<KeyCode> {
+    if let Some(c) = parse_char(text) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match text {
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Backspace,
+        "Esc" => KeyCode::Esc,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Ins" => KeyCode::Ins,
+        "Delete" => KeyCode::Delete,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        _ => parse_function_key(text)?,
+    };
+    Some(code)
+}
+
+/// Parse a key from the same syntax as its `Display`, e.g. `<q>`, `<Ctrl+c>`,
+/// `<Alt+Space>`, `<Enter>`, `<F5>`, `<Ctrl+Alt+Left>`, `<Super+s>`
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .strip_prefix('<')
+            .and_then(|it| it.strip_suffix('>'))
+            .filter(|it| !it.is_empty())
+            .ok_or_else(|| ParseKeyError::MissingBrackets(s.to_string()))?;
+
+        // A single character is never a modifier nor a named key, e.g. `<+>`
+        if let Some(c) = parse_char(inner) {
+            return Ok(Key::Char(c));
+        }
+        match inner {
+            "BackTab" => return Ok(Key::BackTab),
+            "Unknown" => return Ok(Key::Unknown),
+            _ => {}
+        }
+
+        // Modifiers could be in any order, e.g. `<Alt+Ctrl+x>`
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = inner;
+        while let Some((name, modifier)) = MODIFIERS.iter().find(|(name, _)| rest.starts_with(name))
+        {
+            modifiers |= *modifier;
+            rest = &rest[name.len()..];
+        }
+        if rest.is_empty() {
+            return Err(ParseKeyError::MissingKey(s.to_string()));
+        }
+
+        let code = parse_code(rest).ok_or_else(|| ParseKeyError::UnknownKey(s.to_string()))?;
+        // The binding would never match the key typed by the terminal
+        if let KeyCode::Char(c) = code {
+            if modifiers.contains(KeyModifiers::SHIFT) && shifted(c).is_none() {
+                return Err(ParseKeyError::ShiftedChar(s.to_string()));
+            }
+        }
+        Ok(Key::new(modifiers, code))
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
 impl From<event::KeyEvent> for Key {
     fn from(key_event: event::KeyEvent) -> Self {
-        match key_event {
-            event::KeyEvent {
-                code: event::KeyCode::Esc,
-                ..
-            } => Key::Esc,
-            event::KeyEvent {
-                code: event::KeyCode::Backspace,
-                ..
-            } => Key::Backspace,
-            event::KeyEvent {
-                code: event::KeyCode::Left,
-                ..
-            } => Key::Left,
-            event::KeyEvent {
-                code: event::KeyCode::Right,
-                ..
-            } => Key::Right,
-            event::KeyEvent {
-                code: event::KeyCode::Up,
-                ..
-            } => Key::Up,
-            event::KeyEvent {
-                code: event::KeyCode::Down,
-                ..
-            } => Key::Down,
-            event::KeyEvent {
-                code: event::KeyCode::Home,
-                ..
-            } => Key::Home,
-            event::KeyEvent {
-                code: event::KeyCode::End,
-                ..
-            } => Key::End,
-            event::KeyEvent {
-                code: event::KeyCode::PageUp,
-                ..
-            } => Key::PageUp,
-            event::KeyEvent {
-                code: event::KeyCode::PageDown,
-                ..
-            } => Key::PageDown,
-            event::KeyEvent {
-                code: event::KeyCode::Delete,
-                ..
-            } => Key::Delete,
-            event::KeyEvent {
-                code: event::KeyCode::Insert,
-                ..
-            } => Key::Ins,
-            event::KeyEvent {
-                code: event::KeyCode::F(n),
-                ..
-            } => Key::from_f(n),
-            event::KeyEvent {
-                code: event::KeyCode::Enter,
-                ..
-            } => Key::Enter,
-            event::KeyEvent {
-                code: event::KeyCode::Tab,
-                ..
-            } => Key::Tab,
-
-            // First check for char + modifier
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                modifiers: event::KeyModifiers::ALT,
-            } => Key::Alt(c),
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                modifiers: event::KeyModifiers::CONTROL,
-            } => Key::Ctrl(c),
-
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                ..
-            } => Key::Char(c),
-
-            _ => Key::Unknown,
+        let code = match key_event.code {
+            event::KeyCode::Backspace => KeyCode::Backspace,
+            event::KeyCode::Enter => KeyCode::Enter,
+            event::KeyCode::Left => KeyCode::Left,
+            event::KeyCode::Right => KeyCode::Right,
+            event::KeyCode::Up => KeyCode::Up,
+            event::KeyCode::Down => KeyCode::Down,
+            event::KeyCode::Home => KeyCode::Home,
+            event::KeyCode::End => KeyCode::End,
+            event::KeyCode::PageUp => KeyCode::PageUp,
+            event::KeyCode::PageDown => KeyCode::PageDown,
+            event::KeyCode::Tab => KeyCode::Tab,
+            event::KeyCode::BackTab => {
+                let modifiers = key_event.modifiers | KeyModifiers::SHIFT;
+                return Key::new(modifiers, KeyCode::Tab);
+            }
+            event::KeyCode::Delete => KeyCode::Delete,
+            event::KeyCode::Insert => KeyCode::Ins,
+            event::KeyCode::F(n) => KeyCode::F(n),
+            event::KeyCode::Char(c) => KeyCode::Char(c),
+            event::KeyCode::Esc => KeyCode::Esc,
+            // The other keys are only reported with the keyboard enhancement
+            _ => return Key::Unknown,
+        };
+        Key::new(key_event.modifiers, code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn any_modifiers() -> impl Strategy<Value = KeyModifiers> {
+        // Ctrl, Alt, Shift, Super, Hyper and Meta
+        (0..64_u8).prop_map(KeyModifiers::from_bits_truncate)
+    }
+
+    fn any_code() -> impl Strategy<Value = KeyCode> {
+        prop_oneof![
+            any::<char>().prop_map(KeyCode::Char),
+            (0..=12_u8).prop_map(KeyCode::F),
+            prop::sample::select(vec![
+                KeyCode::Enter,
+                KeyCode::Tab,
+                KeyCode::Backspace,
+                KeyCode::Esc,
+                KeyCode::Left,
+                KeyCode::Right,
+                KeyCode::Up,
+                KeyCode::Down,
+                KeyCode::Ins,
+                KeyCode::Delete,
+                KeyCode::Home,
+                KeyCode::End,
+                KeyCode::PageUp,
+                KeyCode::PageDown,
+            ]),
+        ]
+    }
+
+    fn any_key() -> impl Strategy<Value = Key> {
+        prop_oneof![
+            any::<char>().prop_map(Key::Char),
+            any::<char>().prop_map(Key::Ctrl),
+            any::<char>().prop_map(Key::Alt),
+            (0..=12_u8).prop_map(Key::from_f),
+            (any_modifiers(), any_code()).prop_map(|(modifiers, code)| Key::new(modifiers, code)),
+            prop::sample::select(vec![
+                Key::Enter,
+                Key::Tab,
+                Key::BackTab,
+                Key::Backspace,
+                Key::Esc,
+                Key::Left,
+                Key::Right,
+                Key::Up,
+                Key::Down,
+                Key::Ins,
+                Key::Delete,
+                Key::Home,
+                Key::End,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Unknown,
+            ]),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn should_parse_displayed_key(key in any_key()) {
+            let text = key.to_string();
+            prop_assert_eq!(text.parse::<Key>(), Ok(key));
+        }
+
+        #[test]
+        fn should_parse_displayed_raw_key(modifiers in any_modifiers(), code in any_code()) {
+            let key = Key::new(modifiers, code);
+            prop_assert_eq!(key.to_string().parse::<Key>(), Ok(key));
+            // The key is in normal form
+            if let Some((modifiers, code)) = key.decompose() {
+                prop_assert_eq!(Key::new(modifiers, code), key);
+            }
+        }
+
+        #[test]
+        fn should_not_panic_on_any_text(text in "\\PC*") {
+            let _ = text.parse::<Key>();
         }
     }
+
+    #[test]
+    fn should_parse_key() {
+        assert_eq!("<q>".parse(), Ok(Key::Char('q')));
+        assert_eq!("<>>".parse(), Ok(Key::Char('>')));
+        assert_eq!("<Space>".parse(), Ok(Key::Char(' ')));
+        assert_eq!("<Ctrl+c>".parse(), Ok(Key::Ctrl('c')));
+        assert_eq!("<Ctrl++>".parse(), Ok(Key::Ctrl('+')));
+        assert_eq!("<Alt+Space>".parse(), Ok(Key::Alt(' ')));
+        assert_eq!("<PageDown>".parse(), Ok(Key::PageDown));
+        assert_eq!("<F12>".parse(), Ok(Key::F12));
+        assert_eq!("<Shift+Tab>".parse(), Ok(Key::BackTab));
+        assert_eq!("<Shift+a>".parse(), Ok(Key::Char('A')));
+        assert_eq!("<Shift+A>".parse(), Ok(Key::Char('A')));
+        assert_eq!("<Ctrl+Shift+c>".parse(), Ok(Key::Ctrl('C')));
+        assert_eq!(
+            "<Alt+Ctrl+x>".parse(),
+            Ok(Key::new(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x')
+            ))
+        );
+        let key = "<Ctrl+Left>".parse::<Key>().unwrap();
+        assert_eq!(
+            key.decompose(),
+            Some((KeyModifiers::CONTROL, KeyCode::Left))
+        );
+        let key = "<Hyper+Up>".parse::<Key>().unwrap();
+        assert_eq!(key.decompose(), Some((KeyModifiers::HYPER, KeyCode::Up)));
+    }
+
+    #[test]
+    fn should_display_modified_key() {
+        let key = Key::new(
+            KeyModifiers::ALT | KeyModifiers::CONTROL,
+            KeyCode::Char(' '),
+        );
+        assert_eq!(key.to_string(), "<Ctrl+Alt+Space>");
+        let key = Key::new(KeyModifiers::SHIFT | KeyModifiers::ALT, KeyCode::Enter);
+        assert_eq!(key.to_string(), "<Alt+Shift+Enter>");
+        let key = Key::new(KeyModifiers::META | KeyModifiers::SUPER, KeyCode::Char('s'));
+        assert_eq!(key.to_string(), "<Super+Meta+s>");
+        assert_eq!(Key::BackTab.to_string(), "<BackTab>");
+    }
+
+    #[test]
+    fn should_convert_key_event() {
+        let key_event = |code, modifiers| Key::from(event::KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            key_event(event::KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Key::Ctrl('c')
+        );
+        assert_eq!(
+            key_event(event::KeyCode::Char('A'), KeyModifiers::SHIFT),
+            Key::Char('A')
+        );
+        assert_eq!(
+            key_event(event::KeyCode::BackTab, KeyModifiers::SHIFT),
+            Key::BackTab
+        );
+        assert_eq!(
+            key_event(
+                event::KeyCode::Char('c'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            Key::Ctrl('C')
+        );
+        assert_eq!(
+            key_event(event::KeyCode::Left, KeyModifiers::CONTROL),
+            Key::new(KeyModifiers::CONTROL, KeyCode::Left)
+        );
+        assert_eq!(key_event(event::KeyCode::F(5), KeyModifiers::NONE), Key::F5);
+        assert_eq!(
+            key_event(event::KeyCode::F(20), KeyModifiers::NONE),
+            Key::Unknown
+        );
+    }
+
+    #[test]
+    fn should_not_parse_invalid_key() {
+        let invalid = |s: &str| s.parse::<Key>().unwrap_err();
+
+        assert_eq!(invalid("q"), ParseKeyError::MissingBrackets("q".into()));
+        assert_eq!(invalid("<>"), ParseKeyError::MissingBrackets("<>".into()));
+        assert_eq!(
+            invalid("<Ctrl+>"),
+            ParseKeyError::MissingKey("<Ctrl+>".into())
+        );
+        assert_eq!(
+            invalid("<Ctrl+Unknown>"),
+            ParseKeyError::UnknownKey("<Ctrl+Unknown>".into())
+        );
+        assert_eq!(invalid("<F13>"), ParseKeyError::UnknownKey("<F13>".into()));
+        assert_eq!(
+            invalid("<Shift+1>"),
+            ParseKeyError::ShiftedChar("<Shift+1>".into())
+        );
+        assert_eq!(
+            invalid("<Plop>"),
+            ParseKeyError::UnknownKey("<Plop>".into())
+        );
+    }
 }
@@ -1,11 +1,26 @@
+use std::io;
+
 use self::key::Key;
+use self::mouse::Mouse;
 
 pub mod events;
 pub mod key;
+pub mod mouse;
+pub mod sequence;
 
 pub enum InputEvent {
     /// An input event occurred.
     Input(Key),
+    /// A mouse event occurred.
+    Mouse(Mouse),
+    /// A text has been pasted, with the bracketed paste mode.
+    Paste(String),
+    /// The terminal has been resized, with the new width and height.
+    Resize(u16, u16),
     /// An tick event occurred.
     Tick,
+    /// A termination signal has been received, e.g. `SIGTERM`.
+    Terminate(&'static str),
+    /// The inputs cannot be read anymore.
+    Error(io::Error),
 }
@@ -0,0 +1,82 @@
+use crossterm::event::{self, MouseButton, MouseEventKind};
+
+/// Represents a mouse event, with the column and the row of the cursor.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum Mouse {
+    /// Left button pressed
+    Click(u16, u16),
+    /// Moved with the left button pressed
+    Drag(u16, u16),
+    /// Wheel scrolled upwards
+    ScrollUp(u16, u16),
+    /// Wheel scrolled downwards
+    ScrollDown(u16, u16),
+}
+
+impl Mouse {
+    /// Build a mouse event from crossterm, other buttons and moves are ignored
+    pub fn from_event(mouse_event: event::MouseEvent) -> Option<Mouse> {
+        let event::MouseEvent { column, row, .. } = mouse_event;
+        let mouse = match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => Mouse::Click(column, row),
+            MouseEventKind::Drag(MouseButton::Left) => Mouse::Drag(column, row),
+            MouseEventKind::ScrollUp => Mouse::ScrollUp(column, row),
+            MouseEventKind::ScrollDown => Mouse::ScrollDown(column, row),
+            _ => return None,
+        };
+        Some(mouse)
+    }
+
+    /// The column and the row of the cursor
+    pub fn position(&self) -> (u16, u16) {
+        match *self {
+            Mouse::Click(x, y)
+            | Mouse::Drag(x, y)
+            | Mouse::ScrollUp(x, y)
+            | Mouse::ScrollDown(x, y) => (x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyModifiers, MouseEvent};
+
+    use super::*;
+
+    fn mouse_event(kind: MouseEventKind) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column: 3,
+            row: 7,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn should_convert_mouse_event() {
+        let event = mouse_event(MouseEventKind::Down(MouseButton::Left));
+        assert_eq!(Mouse::from_event(event), Some(Mouse::Click(3, 7)));
+        let event = mouse_event(MouseEventKind::Drag(MouseButton::Left));
+        assert_eq!(Mouse::from_event(event), Some(Mouse::Drag(3, 7)));
+        let event = mouse_event(MouseEventKind::ScrollUp);
+        assert_eq!(Mouse::from_event(event), Some(Mouse::ScrollUp(3, 7)));
+        let event = mouse_event(MouseEventKind::ScrollDown);
+        assert_eq!(Mouse::from_event(event), Some(Mouse::ScrollDown(3, 7)));
+        assert_eq!(Mouse::ScrollDown(3, 7).position(), (3, 7));
+        assert_eq!(Mouse::Drag(3, 7).position(), (3, 7));
+    }
+
+    #[test]
+    fn should_ignore_other_mouse_events() {
+        let kinds = [
+            MouseEventKind::Down(MouseButton::Right),
+            MouseEventKind::Up(MouseButton::Left),
+            MouseEventKind::Drag(MouseButton::Right),
+            MouseEventKind::Moved,
+        ];
+        for kind in kinds {
+            assert_eq!(Mouse::from_event(mouse_event(kind)), None);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+
+use super::key::{Key, ParseKeyError};
+
+/// A sequence of keys pressed one after the other, e.g. `<g> <g>` or `<Ctrl+x> <Ctrl+s>`
+#[derive(PartialEq, Eq, Clone, Hash, Debug)]
+pub struct KeySequence(Vec<Key>);
+
+impl KeySequence {
+    /// The keys of the sequence
+    pub fn keys(&self) -> &[Key] {
+        self.0.as_slice()
+    }
+
+    /// If the sequence is longer and starts with the given keys
+    pub fn is_continued_by(&self, keys: &[Key]) -> bool {
+        self.0.len() > keys.len() && self.0.starts_with(keys)
+    }
+}
+
+impl From<Key> for KeySequence {
+    fn from(key: Key) -> Self {
+        Self(vec![key])
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let keys = self
+            .0
+            .iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", keys)
+    }
+}
+
+/// Parse whitespace separated keys, e.g. `<Ctrl+x> <Ctrl+s>`
+impl FromStr for KeySequence {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Key>, _>>()?;
+        if keys.is_empty() {
+            return Err(ParseKeyError::Empty);
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_sequence() {
+        let sequence = "<Ctrl+x>  <Ctrl+s>".parse::<KeySequence>().unwrap();
+        assert_eq!(sequence.keys(), &[Key::Ctrl('x'), Key::Ctrl('s')]);
+        assert_eq!(sequence.to_string(), "<Ctrl+x> <Ctrl+s>");
+    }
+
+    #[test]
+    fn should_not_parse_empty_sequence() {
+        assert_eq!("".parse::<KeySequence>(), Err(ParseKeyError::Empty));
+        assert_eq!(" ".parse::<KeySequence>(), Err(ParseKeyError::Empty));
+    }
+
+    #[test]
+    fn should_be_continued_by_prefix() {
+        let sequence = "<g> <g>".parse::<KeySequence>().unwrap();
+        assert!(sequence.is_continued_by(&[Key::Char('g')]));
+        assert!(!sequence.is_continued_by(&[Key::Char('g'), Key::Char('g')]));
+        assert!(!sequence.is_continued_by(&[Key::Char('q')]));
+    }
+}
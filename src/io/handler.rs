@@ -1,57 +1,376 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
-use std::time::Duration;
 
-use eyre::Result;
-use log::{error, info};
+use futures::FutureExt;
+use log::{error, info, warn};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
 
-use super::IoEvent;
-use crate::app::App;
+use super::progress::ProgressReporter;
+use super::{send, IoError, IoHandler, IoMessage, IoRequest, Retry, TaskId, TaskStatus};
 
-/// In the IO thread, we handle IO event without blocking the UI thread
-pub struct IoAsyncHandler {
-    app: Arc<tokio::sync::Mutex<App>>,
+tokio::task_local! {
+    /// Set while a task runs, on any thread of the runtime
+    static IO_TASK: TaskId;
 }
 
-impl IoAsyncHandler {
-    pub fn new(app: Arc<tokio::sync::Mutex<App>>) -> Self {
-        Self { app }
+/// The current code runs in an IO task, its panics are caught to fail the task
+pub(crate) fn is_io_task() -> bool {
+    IO_TASK.try_with(|_| ()).is_ok()
+}
+
+/// The messages sent by the tasks of an [`IoHandler`]
+type Message<H> = IoMessage<<H as IoHandler>::Outcome, <H as IoHandler>::Error>;
+
+/// In the IO thread, we handle IO event without blocking the UI thread.
+/// Each event is run as a task, at most `max_tasks` tasks are running at the same time.
+/// The App is never locked here, the results are sent back as [`IoMessage`]
+pub struct IoAsyncHandler<H: IoHandler> {
+    handler: Arc<H>,
+    message_tx: mpsc::Sender<Message<H>>,
+    /// Limit the running tasks, the others are queued
+    semaphore: Arc<Semaphore>,
+    /// Unfinished tasks, could be cancelled
+    tasks: HashMap<TaskId, JoinHandle<()>>,
+}
+
+impl<H: IoHandler> IoAsyncHandler<H> {
+    pub fn new(handler: H, message_tx: mpsc::Sender<Message<H>>, max_tasks: usize) -> Self {
+        let handler = Arc::new(handler);
+        let semaphore = Arc::new(Semaphore::new(max_tasks.max(1)));
+        let tasks = HashMap::new();
+        Self {
+            handler,
+            message_tx,
+            semaphore,
+            tasks,
+        }
     }
 
-    /// We could be async here
-    pub async fn handle_io_event(&mut self, io_event: IoEvent) {
-        let result = match io_event {
-            IoEvent::Initialize => self.do_initialize().await,
-            IoEvent::Sleep(duration) => self.do_sleep(duration).await,
+    /// Handle the requests until the sender is dropped, then abort the remaining tasks
+    pub async fn run(mut self, mut io_rx: mpsc::Receiver<IoRequest<H::Event>>) {
+        while let Some(request) = io_rx.recv().await {
+            self.handle_io_request(request).await;
+        }
+        for task in self.tasks.values() {
+            task.abort();
+        }
+    }
+
+    /// Spawn or cancel a task, it never wait for the task completion
+    pub async fn handle_io_request(&mut self, request: IoRequest<H::Event>) {
+        // Forget the finished tasks
+        self.tasks.retain(|_, task| !task.is_finished());
+
+        match request {
+            IoRequest::Run(id, io_event) => {
+                send(&self.message_tx, IoMessage::Task(id, TaskStatus::Queued)).await;
+                let handler = Arc::clone(&self.handler);
+                let semaphore = Arc::clone(&self.semaphore);
+                let message_tx = self.message_tx.clone();
+                let task = tokio::spawn(async move {
+                    let run = run_task(handler, id, io_event, semaphore, message_tx.clone());
+                    let run = IO_TASK.scope(id, run);
+                    // A panic of the handler fails the task, instead of leaving it running
+                    if let Err(panic) = AssertUnwindSafe(run).catch_unwind().await {
+                        let error = IoError::Panicked(panic_message(panic.as_ref()));
+                        error!("Task {} has failed: {}", id, error);
+                        send(&message_tx, IoMessage::Finished(id, Err(error))).await;
+                    }
+                });
+                self.tasks.insert(id, task);
+            }
+            IoRequest::Cancel(id) => match self.tasks.remove(&id) {
+                Some(task) => {
+                    // The permit is released when the task is dropped
+                    task.abort();
+                    // The task could have finished meanwhile, it's only cancelled if interrupted
+                    match task.await {
+                        Err(err) if err.is_cancelled() => {
+                            info!("🛑 Task {} cancelled", id);
+                            send(&self.message_tx, IoMessage::Task(id, TaskStatus::Cancelled))
+                                .await;
+                        }
+                        _ => warn!("Task {} is already finished", id),
+                    }
+                }
+                None => warn!("Task {} is already finished", id),
+            },
+        }
+    }
+}
+
+/// Wait for a permit, then run the IO event with its policy
+async fn run_task<H: IoHandler>(
+    handler: Arc<H>,
+    id: TaskId,
+    io_event: H::Event,
+    semaphore: Arc<Semaphore>,
+    message_tx: mpsc::Sender<Message<H>>,
+) {
+    let Some(mut permit) = acquire(&semaphore, id, &message_tx).await else {
+        return;
+    };
+    send(&message_tx, IoMessage::Task(id, TaskStatus::Running)).await;
+
+    let policy = handler.policy(&io_event);
+    let mut retry = 0;
+    let result = loop {
+        // Each attempt has its own progress
+        let reporter = ProgressReporter::new(id, message_tx.clone());
+        let attempt = handler.handle(&io_event, &reporter);
+        let result = match policy.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, attempt).await {
+                Ok(result) => result.map_err(IoError::Failed),
+                Err(_) => Err(IoError::Timeout(timeout)),
+            },
+            None => attempt.await.map_err(IoError::Failed),
         };
+        match result {
+            Err(error) if policy.should_retry(retry + 1) && handler.is_retryable(&error) => {
+                retry += 1;
+                let delay = policy.backoff.delay(retry);
+                warn!("Task {} failed: {}, retry in {:?}", id, error, delay);
+                let max_retries = policy.max_retries;
+                let error = error.to_string();
+                let retry = Retry {
+                    retry,
+                    max_retries,
+                    delay,
+                    error,
+                };
+                send(&message_tx, IoMessage::Retry(id, retry)).await;
+                // The queued tasks could run during the backoff
+                drop(permit);
+                tokio::time::sleep(delay).await;
+                permit = match acquire(&semaphore, id, &message_tx).await {
+                    Some(permit) => permit,
+                    None => return,
+                };
+            }
+            result => break result,
+        }
+    };
+
+    drop(permit);
+
+    if let Err(err) = &result {
+        error!("Oops, something wrong happen: {}", err);
+    }
+    send(&message_tx, IoMessage::Finished(id, result)).await;
+}
 
-        if let Err(err) = result {
-            error!("Oops, something wrong happen: {:?}", err);
+/// Wait for a permit to run an attempt, the task fails if the handler is stopped
+async fn acquire<O, E>(
+    semaphore: &Arc<Semaphore>,
+    id: TaskId,
+    message_tx: &mpsc::Sender<IoMessage<O, E>>,
+) -> Option<OwnedSemaphorePermit> {
+    match Arc::clone(semaphore).acquire_owned().await {
+        Ok(permit) => Some(permit),
+        Err(err) => {
+            error!("Task {} cannot run: {}", id, err);
+            send(message_tx, IoMessage::Finished(id, Err(IoError::Closed))).await;
+            None
         }
+    }
+}
 
-        let mut app = self.app.lock().await;
-        app.loaded();
+/// The message given to `panic!`, if it's a string
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => String::from("unknown panic"),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
 
-    /// We use dummy implementation here, just wait 1s
-    async fn do_initialize(&mut self) -> Result<()> {
-        info!("🚀 Initialize the application");
-        let mut app = self.app.lock().await;
-        tokio::time::sleep(Duration::from_secs(1)).await;
-        app.initialized(); // we could update the app state
-        info!("👍 Application initialized");
+    use super::*;
+    use crate::io::policy::Policy;
+    use crate::testing::{TestEvent, TestHandler};
 
-        Ok(())
+    type TestMessage = Message<TestHandler>;
+
+    fn task(id: u64, status: TaskStatus) -> Option<TestMessage> {
+        Some(IoMessage::Task(TaskId(id), status))
     }
 
-    /// Just take a little break
-    async fn do_sleep(&mut self, duration: Duration) -> Result<()> {
-        info!("😴 Go sleeping for {:?}...", duration);
-        tokio::time::sleep(duration).await;
-        info!("⏰ Wake up !");
-        // Notify the app for having slept
-        let mut app = self.app.lock().await;
-        app.slept();
+    fn sleep(millis: u64) -> TestEvent {
+        TestEvent::Sleep(Duration::from_millis(millis))
+    }
+
+    /// The next message, skipping the progress
+    async fn recv(rx: &mut mpsc::Receiver<TestMessage>) -> Option<TestMessage> {
+        loop {
+            match rx.recv().await {
+                Some(IoMessage::Progress(..)) => continue,
+                message => return message,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn should_send_messages_without_app() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut handler = IoAsyncHandler::new(TestHandler::default(), tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(1)))
+            .await;
+
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+        let outcome = Ok(Duration::from_millis(1));
+        let finished = Some(IoMessage::Finished(TaskId(1), outcome));
+        assert_eq!(recv(&mut rx).await, finished);
+    }
+
+    #[tokio::test]
+    async fn should_send_typed_errors() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut handler = IoAsyncHandler::new(TestHandler::default(), tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(0)))
+            .await;
+
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+        let error = Err(IoError::Failed(String::from("Cannot sleep for 0ns")));
+        let finished = Some(IoMessage::Finished(TaskId(1), error));
+        assert_eq!(recv(&mut rx).await, finished);
+    }
+
+    #[tokio::test]
+    async fn should_fail_task_on_panic() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut handler = IoAsyncHandler::new(TestHandler::default(), tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), TestEvent::Panic))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+        let error = IoError::Panicked(String::from("Oops, the handler has panicked"));
+        let finished = Some(IoMessage::Finished(TaskId(1), Err(error)));
+        assert_eq!(recv(&mut rx).await, finished);
+
+        // The permit is released
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(2), sleep(1)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Running));
+    }
+
+    #[tokio::test]
+    async fn should_know_the_io_tasks() {
+        assert!(!is_io_task());
+        IO_TASK
+            .scope(TaskId(1), async { assert!(is_io_task()) })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn should_queue_and_cancel_tasks() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut handler = IoAsyncHandler::new(TestHandler::default(), tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(10_000)))
+            .await;
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(2), sleep(10_000)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+
+        // The second task runs once the first one is cancelled
+        handler
+            .handle_io_request(IoRequest::Cancel(TaskId(1)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Running));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn should_not_cancel_finished_task() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut handler = IoAsyncHandler::new(TestHandler::default(), tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(1)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+        assert!(matches!(
+            recv(&mut rx).await,
+            Some(IoMessage::Finished(TaskId(1), Ok(_)))
+        ));
+
+        // The finished task is not reaped yet
+        handler
+            .handle_io_request(IoRequest::Cancel(TaskId(1)))
+            .await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_retry_on_timeout() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let policy = Policy::default().timeout(Duration::from_secs(1)).retries(2);
+        let mut handler = IoAsyncHandler::new(TestHandler { policy }, tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(10_000)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+
+        let timeout = IoError::Timeout(Duration::from_secs(1));
+        for expected in 1..=2 {
+            match recv(&mut rx).await {
+                Some(IoMessage::Retry(TaskId(1), retry)) => {
+                    assert_eq!(retry.retry, expected);
+                    assert_eq!(retry.max_retries, 2);
+                    assert_eq!(retry.error, timeout.to_string());
+                    assert!(retry.delay <= policy.backoff.max);
+                }
+                other => panic!("Unexpected {:?}", other),
+            }
+        }
+        let finished = Some(IoMessage::Finished(TaskId(1), Err(timeout)));
+        assert_eq!(recv(&mut rx).await, finished);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_release_permit_during_backoff() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let policy = Policy::default().timeout(Duration::from_secs(1)).retries(1);
+        let mut handler = IoAsyncHandler::new(TestHandler { policy }, tx, 1);
+
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(1), sleep(10_000)))
+            .await;
+        handler
+            .handle_io_request(IoRequest::Run(TaskId(2), sleep(10)))
+            .await;
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Queued));
+        assert_eq!(recv(&mut rx).await, task(1, TaskStatus::Running));
+        assert!(matches!(
+            recv(&mut rx).await,
+            Some(IoMessage::Retry(TaskId(1), _))
+        ));
 
-        Ok(())
+        // The queued task runs while the first one waits for its retry
+        assert_eq!(recv(&mut rx).await, task(2, TaskStatus::Running));
     }
 }
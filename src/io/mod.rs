@@ -1,9 +1,154 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::future::Future;
 use std::time::Duration;
 
+use log::error;
+use tokio::sync::mpsc;
+
+use self::policy::Policy;
+use self::progress::{Progress, ProgressReporter};
+
 pub mod handler;
-// For this dummy application we only need two IO event
-#[derive(Debug, Clone)]
-pub enum IoEvent {
-    Initialize,      // Launch to initialize the application
-    Sleep(Duration), // Just take a little break
+pub mod policy;
+pub mod progress;
+
+/// Run the IO events of an application, out of the UI thread.
+///
+/// Each event is run as a task by the [`IoAsyncHandler`](handler::IoAsyncHandler),
+/// with the timeout and retries of its [`Policy`]
+pub trait IoHandler: Send + Sync + 'static {
+    /// The events dispatched by the application, displayed in the task list
+    type Event: Debug + Display + Clone + Send + Sync + 'static;
+    /// The successful outcome of an event
+    type Outcome: Debug + Send + 'static;
+    /// The failure of an event
+    type Error: Debug + Display + Send + 'static;
+
+    /// Run an event, each attempt call this method again
+    fn handle(
+        &self,
+        event: &Self::Event,
+        reporter: &ProgressReporter<Self::Outcome, Self::Error>,
+    ) -> impl Future<Output = Result<Self::Outcome, Self::Error>> + Send;
+
+    /// The timeout and retries of an event, no timeout nor retry by default
+    fn policy(&self, _event: &Self::Event) -> Policy {
+        Policy::default()
+    }
+
+    /// Another attempt could succeed, only the timeouts are retried by default
+    fn is_retryable(&self, error: &IoError<Self::Error>) -> bool {
+        matches!(error, IoError::Timeout(_))
+    }
+}
+
+/// Identify an IO task, given by the App when dispatching an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(pub u64);
+
+impl Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Requests sent to the IO handler
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoRequest<E> {
+    /// Run the event as a new task
+    Run(TaskId, E),
+    /// Abort a queued or running task
+    Cancel(TaskId),
+}
+
+/// The lifecycle of an IO task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// The task is waiting or running
+    pub fn is_active(&self) -> bool {
+        matches!(self, TaskStatus::Queued | TaskStatus::Running)
+    }
+}
+
+impl Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            TaskStatus::Queued => "queued",
+            TaskStatus::Running => "running",
+            TaskStatus::Done => "done",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Cancelled => "cancelled",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// The failure of an IO event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoError<E> {
+    /// The handler has failed
+    Failed(E),
+    /// An attempt has lasted too long
+    Timeout(Duration),
+    /// The handler has panicked, with the panic message
+    Panicked(String),
+    /// The IO handler is stopped
+    Closed,
+}
+
+impl<E: Display> Display for IoError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Failed(err) => write!(f, "{}", err),
+            IoError::Timeout(timeout) => write!(f, "Timed out after {:?}", timeout),
+            IoError::Panicked(message) => write!(f, "Panicked: {}", message),
+            IoError::Closed => write!(f, "The IO handler is stopped"),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for IoError<E> {}
+
+/// A retry scheduled after a failed attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retry {
+    /// The retry number, starting at 1
+    pub retry: u32,
+    pub max_retries: u32,
+    /// Delay before the next attempt
+    pub delay: Duration,
+    /// Why the last attempt has failed
+    pub error: String,
+}
+
+/// The results of the IO events, sent back to the UI loop that applies them to the App
+#[derive(Debug, Clone, PartialEq)]
+pub enum IoMessage<O, E> {
+    /// A task has changed
+    Task(TaskId, TaskStatus),
+    /// A running task has progressed
+    Progress(TaskId, Progress),
+    /// A running task will retry
+    Retry(TaskId, Retry),
+    /// A task is done, or has failed
+    Finished(TaskId, Result<O, IoError<E>>),
+}
+
+/// Send a message of a task to the UI, the UI loop could already be stopped
+pub(crate) async fn send<O, E>(
+    message_tx: &mpsc::Sender<IoMessage<O, E>>,
+    message: IoMessage<O, E>,
+) {
+    if message_tx.send(message).await.is_err() {
+        error!("Cannot send a message to the UI");
+    }
 }
@@ -0,0 +1,139 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Exponential backoff between two attempts, with a random jitter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Maximal delay between two attempts
+    pub max: Duration,
+    /// Growth of the delay after each attempt
+    pub multiplier: f64,
+    /// Part of the delay that is random, between 0 and 1
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(200),
+            max: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before the given retry, starting at 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.delay_with(retry, random())
+    }
+
+    /// The delay before the given retry, with a random number between 0 and 1
+    fn delay_with(&self, retry: u32, random: f64) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        // Computed in seconds, the delay could overflow a Duration before being limited
+        let secs = self.initial.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let delay = Duration::try_from_secs_f64(secs).map_or(self.max, |delay| delay.min(self.max));
+        let jitter = if self.jitter.is_nan() {
+            0.0
+        } else {
+            self.jitter.clamp(0.0, 1.0)
+        };
+        delay.mul_f64(1.0 - jitter * random.clamp(0.0, 1.0))
+    }
+}
+
+/// A random number between 0 and 1, from the random keys of the std hasher
+fn random() -> f64 {
+    RandomState::new().hash_one(0_u8) as f64 / u64::MAX as f64
+}
+
+/// How an IO event is run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Policy {
+    /// Maximal duration of an attempt
+    pub timeout: Option<Duration>,
+    /// Number of retries after a failed attempt
+    pub max_retries: u32,
+    pub backoff: Backoff,
+}
+
+impl Policy {
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Another attempt is allowed, the retries start at 1
+    pub fn should_retry(&self, retry: u32) -> bool {
+        retry <= self.max_retries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_grow_exponentially() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(500),
+            multiplier: 2.0,
+            jitter: 0.5,
+        };
+        assert_eq!(backoff.delay_with(1, 0.0), Duration::from_millis(100));
+        assert_eq!(backoff.delay_with(2, 0.0), Duration::from_millis(200));
+        assert_eq!(backoff.delay_with(3, 0.0), Duration::from_millis(400));
+        assert_eq!(backoff.delay_with(4, 0.0), Duration::from_millis(500));
+        assert_eq!(backoff.delay_with(2, 1.0), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn should_limit_delay_of_many_retries() {
+        let backoff = Backoff::default();
+        for retry in [68, 100, 10_000, u32::MAX] {
+            assert_eq!(backoff.delay_with(retry, 0.0), backoff.max);
+        }
+
+        let backoff = Backoff {
+            multiplier: f64::INFINITY,
+            jitter: f64::NAN,
+            ..Backoff::default()
+        };
+        assert_eq!(backoff.delay_with(1, 0.5), backoff.initial);
+        assert_eq!(backoff.delay_with(2, 0.5), backoff.max);
+    }
+
+    #[test]
+    fn should_jitter_in_bounds() {
+        let backoff = Backoff::default();
+        for retry in 1..10 {
+            let delay = backoff.delay(retry);
+            let max = backoff.delay_with(retry, 0.0);
+            assert!(delay <= max && delay >= max / 2, "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn should_limit_retries() {
+        let policy = Policy::default().retries(1);
+        assert!(policy.should_retry(1));
+        assert!(!policy.should_retry(2));
+        assert!(!Policy::default().should_retry(1));
+    }
+}
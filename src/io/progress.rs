@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use super::{send, IoMessage, TaskId};
+
+/// The progress of a running task
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// Between 0 and 1
+    pub ratio: f64,
+    /// The current step
+    pub message: String,
+    /// Estimated remaining time
+    pub eta: Option<Duration>,
+}
+
+/// Given to the IO handlers to report the progress of their task
+pub struct ProgressReporter<O, E> {
+    id: TaskId,
+    started: Instant,
+    message_tx: mpsc::Sender<IoMessage<O, E>>,
+}
+
+impl<O, E> ProgressReporter<O, E> {
+    /// The attempt starts now
+    pub fn new(id: TaskId, message_tx: mpsc::Sender<IoMessage<O, E>>) -> Self {
+        let started = Instant::now();
+        Self {
+            id,
+            started,
+            message_tx,
+        }
+    }
+
+    /// Report a step, the ETA is extrapolated from the elapsed time
+    pub async fn report(&self, ratio: f64, message: impl Into<String>) {
+        // An undefined ratio, e.g. 0 of 0 steps, has not started yet
+        let ratio = if ratio.is_nan() {
+            0.0
+        } else {
+            ratio.clamp(0.0, 1.0)
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let eta = (ratio > 0.0)
+            .then(|| Duration::try_from_secs_f64(elapsed * (1.0 - ratio) / ratio).ok())
+            .flatten();
+        let progress = Progress {
+            ratio,
+            message: message.into(),
+            eta,
+        };
+        send(&self.message_tx, IoMessage::Progress(self.id, progress)).await;
+    }
+
+    /// The task being run
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn should_extrapolate_eta() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let reporter = ProgressReporter::<(), ()>::new(TaskId(1), tx);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        reporter.report(0.25, "Quarter").await;
+        match rx.recv().await {
+            Some(IoMessage::Progress(_, progress)) => {
+                assert_eq!(progress.eta, Some(Duration::from_secs(9)));
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_report_progress() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let reporter = ProgressReporter::<(), ()>::new(TaskId(1), tx);
+
+        reporter.report(0.0, "Start").await;
+        reporter.report(f64::NAN, "Nothing").await;
+        reporter.report(2.0, "Done").await;
+
+        match rx.recv().await {
+            Some(IoMessage::Progress(TaskId(1), progress)) => {
+                assert_eq!(progress.message, "Start");
+                assert_eq!(progress.eta, None);
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+        match rx.recv().await {
+            Some(IoMessage::Progress(TaskId(1), progress)) => {
+                assert_eq!(progress.ratio, 0.0);
+                assert_eq!(progress.eta, None);
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+        match rx.recv().await {
+            Some(IoMessage::Progress(TaskId(1), progress)) => {
+                assert_eq!(progress.ratio, 1.0);
+                assert_eq!(progress.eta, Some(Duration::ZERO));
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+}
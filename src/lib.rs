@@ -1,63 +1,141 @@
-use std::io::stdout;
-use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use app::model::Model;
 use app::{App, AppReturn};
-use eyre::Result;
+use eyre::{bail, Result};
+use frame::FrameScheduler;
 use inputs::events::Events;
 use inputs::InputEvent;
-use io::IoEvent;
-use tui::backend::CrosstermBackend;
-use tui::Terminal;
+use io::IoMessage;
+use log::info;
+use terminal::TerminalBuilder;
+use tokio::sync::mpsc;
 
-use crate::app::ui;
+use crate::app::ui::{self, UiAreas};
 
 pub mod app;
+pub mod frame;
 pub mod inputs;
 pub mod io;
+pub mod terminal;
+#[cfg(test)]
+mod testing;
 
-pub async fn start_ui(app: &Arc<tokio::sync::Mutex<App>>) -> Result<()> {
-    // Configure Crossterm backend for tui
-    let stdout = stdout();
-    crossterm::terminal::enable_raw_mode()?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    terminal.clear()?;
-    terminal.hide_cursor()?;
+/// The shortest delay between two ticks
+pub const MIN_TICK_RATE: Duration = Duration::from_millis(1);
 
-    // User event handler
-    let tick_rate = Duration::from_millis(200);
-    let mut events = Events::new(tick_rate);
-
-    // Trigger state change from Init to Initialized
-    {
-        let mut app = app.lock().await;
-        // Here we assume the the first load is a long task
-        app.dispatch(IoEvent::Initialize).await;
+/// Options of the UI loop
+#[derive(Debug, Clone, Copy)]
+pub struct UiOptions {
+    terminal: TerminalBuilder,
+    tick_rate: Duration,
+    frame_rate: u16,
+}
+
+impl Default for UiOptions {
+    fn default() -> Self {
+        Self {
+            terminal: TerminalBuilder::default(),
+            tick_rate: Duration::from_millis(200),
+            frame_rate: 30,
+        }
     }
+}
 
-    loop {
-        let mut app = app.lock().await;
+impl UiOptions {
+    /// The terminal setup
+    pub fn terminal(mut self, terminal: TerminalBuilder) -> Self {
+        self.terminal = terminal;
+        self
+    }
 
-        // Render
-        terminal.draw(|rect| ui::draw(rect, &app))?;
+    /// The delay between two [`InputEvent::Tick`], at least [`MIN_TICK_RATE`]
+    pub fn tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = tick_rate.max(MIN_TICK_RATE);
+        self
+    }
 
-        // Handle inputs
-        let result = match events.next().await {
-            InputEvent::Input(key) => app.do_action(key).await,
-            InputEvent::Tick => app.update_on_tick().await,
-        };
-        // Check if we should exit
-        if result == AppReturn::Exit {
-            events.close();
-            break;
-        }
+    /// The maximum number of frames drawn per second,
+    /// a frame is only drawn if the application has changed
+    pub fn frame_rate(mut self, frame_rate: u16) -> Self {
+        self.frame_rate = frame_rate;
+        self
     }
+}
 
-    // Restore the terminal and close application
-    terminal.clear()?;
-    terminal.show_cursor()?;
-    crossterm::terminal::disable_raw_mode()?;
+/// Run the UI loop, the App is owned by this loop and updated with
+/// the inputs and the results of the IO events
+pub async fn start_ui<M: Model>(
+    mut app: App<M>,
+    mut io_rx: mpsc::Receiver<IoMessage<M::Outcome, M::Error>>,
+    options: UiOptions,
+) -> Result<()> {
+    // The terminal is restored on exit, error, or panic
+    let mut terminal = options.terminal.build()?;
+
+    // User event handler
+    let mut events = Events::new(options.tick_rate)?;
+    let mut frames = FrameScheduler::new(options.frame_rate);
+
+    // The model could dispatch its first IO events
+    let mut result = app.init().await;
+
+    loop {
+        // A panic of another task has restored the terminal, nothing could be drawn anymore
+        if !terminal.is_active() {
+            events.close().await;
+            bail!("The terminal has been restored after a panic");
+        }
+        match result {
+            // Check if we should exit
+            AppReturn::Exit => {
+                events.close().await;
+                break;
+            }
+            // Stop reading inputs while the terminal is given back to the shell
+            AppReturn::Suspend => {
+                events.pause().await;
+                terminal.suspend()?;
+                events.resume().await;
+                app.mark_dirty();
+            }
+            AppReturn::Continue => {}
+        }
+
+        result = tokio::select! {
+            _ = frames.tick() => {
+                // Render, only if something has changed
+                if app.take_dirty() {
+                    let started = Instant::now();
+                    let stats = frames.stats();
+                    let mut areas = UiAreas::default();
+                    terminal.draw(|rect| areas = ui::draw(rect, &app, stats))?;
+                    app.set_areas(areas);
+                    frames.record(started);
+                }
+                AppReturn::Continue
+            }
+            // Apply the IO results, the dispatch never waits for the IO loop
+            Some(message) = io_rx.recv() => app.on_io_message(message).await,
+            // Handle inputs
+            event = events.next() => match event {
+                InputEvent::Input(key) => app.do_action(key).await,
+                InputEvent::Mouse(mouse) => app.do_mouse(mouse).await,
+                InputEvent::Paste(text) => app.do_paste(text).await,
+                InputEvent::Resize(width, height) => app.on_resize(width, height).await,
+                InputEvent::Tick => app.update_on_tick().await,
+                InputEvent::Terminate(signal) => {
+                    info!("Terminated by {}", signal);
+                    AppReturn::Exit
+                }
+                InputEvent::Error(err) => {
+                    events.close().await;
+                    return Err(err.into());
+                }
+            },
+        };
+    }
 
+    // The terminal is restored when dropped
     Ok(())
 }
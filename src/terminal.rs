@@ -0,0 +1,303 @@
+use std::io::{self, stdout, Stdout, Write};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use log::error;
+use tui::backend::CrosstermBackend;
+use tui::layout::Rect;
+use tui::terminal::{TerminalOptions, Viewport};
+use tui::Terminal;
+
+/// Where the UI is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenMode {
+    /// In the alternate screen, the user's scrollback is kept untouched
+    Fullscreen,
+    /// In a region of the given height below the prompt
+    Inline(u16),
+}
+
+/// Build the terminal used by the UI
+///
+/// ```no_run
+/// # use plop_tui::terminal::TerminalBuilder;
+/// let terminal = TerminalBuilder::default().inline(20).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalBuilder {
+    screen: ScreenMode,
+    mouse_capture: bool,
+    bracketed_paste: bool,
+}
+
+impl Default for TerminalBuilder {
+    fn default() -> Self {
+        Self {
+            screen: ScreenMode::Fullscreen,
+            mouse_capture: true,
+            bracketed_paste: true,
+        }
+    }
+}
+
+impl TerminalBuilder {
+    /// Render in the alternate screen (default)
+    pub fn fullscreen(mut self) -> Self {
+        self.screen = ScreenMode::Fullscreen;
+        self
+    }
+
+    /// Render in a region of the given height below the prompt
+    pub fn inline(mut self, height: u16) -> Self {
+        self.screen = ScreenMode::Inline(height);
+        self
+    }
+
+    /// Capture mouse events (default)
+    pub fn mouse_capture(mut self, enabled: bool) -> Self {
+        self.mouse_capture = enabled;
+        self
+    }
+
+    /// Receive a pasted text at once, instead of a key per char (default)
+    pub fn bracketed_paste(mut self, enabled: bool) -> Self {
+        self.bracketed_paste = enabled;
+        self
+    }
+
+    /// Configure Crossterm backend for tui
+    ///
+    /// The terminal is restored when the [`TerminalGuard`] is dropped, or on panic
+    pub fn build(self) -> io::Result<TerminalGuard> {
+        install_panic_hook();
+        *active_terminal() = Some(self);
+        ACTIVE.store(true, Ordering::SeqCst);
+        let (terminal, inline_area) = self.enter().inspect_err(|_| {
+            take_active_terminal();
+        })?;
+
+        Ok(TerminalGuard {
+            terminal,
+            builder: self,
+            inline_area,
+        })
+    }
+
+    /// Setup the terminal modes, the new terminal is fully drawn on the next draw call.
+    /// The terminal is restored if a mode cannot be set
+    fn enter(&self) -> io::Result<(Terminal<CrosstermBackend<Stdout>>, Option<Rect>)> {
+        self.setup().inspect_err(|_| {
+            if let Err(err) = self.restore() {
+                error!("Fail to restore the terminal: {}", err);
+            }
+        })
+    }
+
+    fn setup(&self) -> io::Result<(Terminal<CrosstermBackend<Stdout>>, Option<Rect>)> {
+        let mut stdout = stdout();
+
+        // Reserve the region before the raw mode, so new lines scroll the terminal
+        let inline_area = match self.screen {
+            ScreenMode::Fullscreen => None,
+            ScreenMode::Inline(height) => Some(reserve_inline_area(&mut stdout, height)?),
+        };
+
+        crossterm::terminal::enable_raw_mode()?;
+        if self.screen == ScreenMode::Fullscreen {
+            crossterm::execute!(stdout, EnterAlternateScreen)?;
+        }
+        if self.mouse_capture {
+            crossterm::execute!(stdout, EnableMouseCapture)?;
+        }
+        if self.bracketed_paste {
+            crossterm::execute!(stdout, EnableBracketedPaste)?;
+        }
+
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = match inline_area {
+            Some(area) => {
+                let viewport = Viewport::fixed(area);
+                Terminal::with_options(backend, TerminalOptions { viewport })?
+            }
+            None => {
+                let mut terminal = Terminal::new(backend)?;
+                terminal.clear()?;
+                terminal
+            }
+        };
+        terminal.hide_cursor()?;
+
+        Ok((terminal, inline_area))
+    }
+
+    /// Restore the terminal in its original mode,
+    /// every mode is restored even if another one fails, the first error is returned
+    fn restore(&self) -> io::Result<()> {
+        let mut stdout = stdout();
+        let mut results = vec![];
+        if self.bracketed_paste {
+            results.push(crossterm::execute!(stdout, DisableBracketedPaste));
+        }
+        if self.mouse_capture {
+            results.push(crossterm::execute!(stdout, DisableMouseCapture));
+        }
+        if self.screen == ScreenMode::Fullscreen {
+            results.push(crossterm::execute!(stdout, LeaveAlternateScreen));
+        }
+        results.push(crossterm::execute!(stdout, crossterm::cursor::Show));
+        results.push(crossterm::terminal::disable_raw_mode());
+        results.into_iter().collect()
+    }
+}
+
+/// Print new lines to make room for the inline region, just below the cursor
+fn reserve_inline_area(stdout: &mut Stdout, height: u16) -> io::Result<Rect> {
+    let (width, rows) = crossterm::terminal::size()?;
+    let height = height.clamp(1, rows);
+    for _ in 1..height {
+        writeln!(stdout)?;
+    }
+    stdout.flush()?;
+    let (_, bottom) = crossterm::cursor::position()?;
+    let top = (bottom + 1).saturating_sub(height);
+
+    Ok(Rect::new(0, top, width, height))
+}
+
+/// The setup of the terminal currently used by the UI, restored on panic
+static ACTIVE_TERMINAL: Mutex<Option<TerminalBuilder>> = Mutex::new(None);
+/// The active terminal is not restored yet, either by the panic hook or by the guard
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+fn active_terminal() -> MutexGuard<'static, Option<TerminalBuilder>> {
+    ACTIVE_TERMINAL
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Restore the active terminal before printing the panic message,
+/// the hook is installed once for all the built terminals.
+/// The UI could run on any thread of the runtime, the UI loop stops once the terminal is restored.
+/// The panics of the IO tasks fail their task, they are logged instead of printed over the UI
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if crate::io::handler::is_io_task() {
+                error!("{}", info);
+                return;
+            }
+            if let Some(builder) = take_active_terminal() {
+                // Nothing more to do if we cannot restore the terminal
+                let _ = builder.restore();
+            }
+            hook(info);
+        }));
+    });
+}
+
+/// The active terminal, only once: the first caller restores it
+fn take_active_terminal() -> Option<TerminalBuilder> {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        active_terminal().take()
+    } else {
+        None
+    }
+}
+
+/// The terminal used by the UI, restored when dropped, even on error or panic
+pub struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    builder: TerminalBuilder,
+    inline_area: Option<Rect>,
+}
+
+impl TerminalGuard {
+    /// The terminal is not restored yet, e.g. by a panic of another task
+    pub fn is_active(&self) -> bool {
+        ACTIVE.load(Ordering::SeqCst)
+    }
+
+    /// Restore the terminal and stop the process with `SIGTSTP`,
+    /// the terminal is setup again when the process is continued with `SIGCONT`
+    #[cfg(unix)]
+    pub fn suspend(&mut self) -> io::Result<()> {
+        self.builder.restore()?;
+        // Safety: raising a signal has no memory effect, this thread stops until `SIGCONT`
+        if unsafe { libc::raise(libc::SIGTSTP) } != 0 {
+            error!("Fail to suspend: {}", io::Error::last_os_error());
+        }
+        // The screen could have been changed meanwhile, the new terminal draw everything
+        let (terminal, inline_area) = self.builder.enter()?;
+        self.terminal = terminal;
+        self.inline_area = inline_area;
+
+        Ok(())
+    }
+
+    /// Suspend is not supported on this platform
+    #[cfg(not(unix))]
+    pub fn suspend(&mut self) -> io::Result<()> {
+        log::warn!("Suspend is not supported on this platform");
+        Ok(())
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Already restored by the panic hook
+        if take_active_terminal().is_none() {
+            return;
+        }
+        // Keep the last inline frame, the prompt goes below
+        if let Some(area) = self.inline_area {
+            let bottom = area.bottom().saturating_sub(1);
+            if let Err(err) = self.terminal.set_cursor(0, bottom) {
+                error!("Fail to move the cursor: {}", err);
+            }
+        }
+        if let Err(err) = self.builder.restore() {
+            error!("Fail to restore the terminal: {}", err);
+        }
+        if self.inline_area.is_some() {
+            println!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_restore_once() {
+        let builder = TerminalBuilder::default();
+        *active_terminal() = Some(builder);
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        let restored = std::thread::spawn(take_active_terminal).join().unwrap();
+        assert_eq!(restored, Some(builder));
+        assert_eq!(take_active_terminal(), None);
+    }
+}
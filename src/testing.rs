@@ -0,0 +1,226 @@
+//! Fixtures of the tests: an action enum, a model, an IO handler, and an app of the model
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::widgets::Paragraph;
+use tui::Frame;
+
+use crate::app::actions::Action;
+use crate::app::command::CommandSpec;
+use crate::app::keymap::Keymap;
+use crate::app::model::{Cmd, Model, ModelCmd, ModelMsg, Msg, Panels};
+use crate::app::notifications::Severity;
+use crate::app::App;
+use crate::inputs::key::Key;
+use crate::io::policy::Policy;
+use crate::io::progress::ProgressReporter;
+use crate::io::{IoHandler, IoRequest};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestAction {
+    Quit,
+    Sleep,
+    IncrementDelay,
+    DecrementDelay,
+}
+
+impl Action for TestAction {
+    fn all() -> &'static [Self] {
+        &[
+            TestAction::Quit,
+            TestAction::Sleep,
+            TestAction::IncrementDelay,
+            TestAction::DecrementDelay,
+        ]
+    }
+
+    fn default_keys(&self) -> &[Key] {
+        match self {
+            TestAction::Quit => &[Key::Ctrl('c'), Key::Char('q')],
+            TestAction::Sleep => &[Key::Char('s')],
+            TestAction::IncrementDelay => &[Key::Char('+')],
+            TestAction::DecrementDelay => &[Key::Char('-')],
+        }
+    }
+}
+
+impl Display for TestAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestEvent {
+    Sleep(Duration),
+    /// A bug of the handler
+    Panic,
+}
+
+impl Display for TestEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestEvent::Sleep(duration) => write!(f, "Sleep {:?}", duration),
+            TestEvent::Panic => write!(f, "Panic"),
+        }
+    }
+}
+
+/// Sleep, reporting the progress
+#[derive(Debug, Default)]
+pub struct TestHandler {
+    pub policy: Policy,
+}
+
+impl IoHandler for TestHandler {
+    type Event = TestEvent;
+    type Outcome = Duration;
+    type Error = String;
+
+    async fn handle(
+        &self,
+        event: &TestEvent,
+        reporter: &ProgressReporter<Duration, String>,
+    ) -> Result<Duration, String> {
+        let duration = match event {
+            TestEvent::Sleep(duration) => duration,
+            TestEvent::Panic => panic!("Oops, the handler has panicked"),
+        };
+        if duration.is_zero() {
+            return Err(format!("Cannot sleep for {:?}", duration));
+        }
+        reporter.report(0.0, "Sleeping").await;
+        tokio::time::sleep(*duration).await;
+        Ok(*duration)
+    }
+
+    fn policy(&self, _event: &TestEvent) -> Policy {
+        self.policy
+    }
+}
+
+/// The screens of the test model, the detail only allows to quit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestScreen {
+    #[default]
+    Main,
+    Detail,
+}
+
+/// Count the sleeps
+#[derive(Debug, Default)]
+pub struct TestModel {
+    pub delay: Duration,
+    pub sleeps: u32,
+    /// The model gets the keys, and ignores them
+    pub focused: bool,
+}
+
+impl Model for TestModel {
+    type Action = TestAction;
+    type Event = TestEvent;
+    type Outcome = Duration;
+    type Error = String;
+    type Screen = TestScreen;
+
+    fn title(&self, screen: &TestScreen) -> String {
+        format!("Test {:?}", screen)
+    }
+
+    fn actions(&self, screen: &TestScreen) -> Vec<TestAction> {
+        match screen {
+            TestScreen::Main => TestAction::all().to_vec(),
+            TestScreen::Detail => vec![TestAction::Quit],
+        }
+    }
+
+    fn commands(&self, _screen: &TestScreen) -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("delay", "<seconds>", "Set the delay"),
+            CommandSpec::new("open", "", "Open the detail"),
+            CommandSpec::new("focus", "", "Focus the input"),
+        ]
+    }
+
+    fn is_focused(&self, _screen: &TestScreen) -> bool {
+        self.focused
+    }
+
+    fn panels(&self, screen: &TestScreen) -> Panels {
+        let tasks = *screen == TestScreen::Main;
+        Panels {
+            tasks,
+            ..Panels::default()
+        }
+    }
+
+    fn update(mut self, _screen: &TestScreen, msg: ModelMsg<Self>) -> (Self, ModelCmd<Self>) {
+        let cmd = match msg {
+            Msg::Action(TestAction::Quit) => Cmd::Exit,
+            Msg::Action(TestAction::Sleep) => Cmd::Dispatch(TestEvent::Sleep(self.delay)),
+            Msg::Action(TestAction::IncrementDelay) => {
+                self.delay += Duration::from_secs(1);
+                Cmd::None
+            }
+            Msg::Action(TestAction::DecrementDelay) => {
+                self.delay = self.delay.saturating_sub(Duration::from_secs(1));
+                Cmd::None
+            }
+            Msg::Outcome(_, result) => {
+                if result.is_ok() {
+                    self.sleeps += 1;
+                }
+                Cmd::None
+            }
+            Msg::Command(command) if command.name == "open" => Cmd::Push(TestScreen::Detail),
+            Msg::Command(command) if command.name == "focus" => Cmd::Focus,
+            Msg::Command(command) => match command.args.first().map(|arg| arg.parse()) {
+                Some(Ok(secs)) => {
+                    self.delay = Duration::from_secs(secs);
+                    Cmd::None
+                }
+                _ => Cmd::notify(Severity::Error, "Invalid delay"),
+            },
+            Msg::Init | Msg::Input(..) | Msg::Key(_) | Msg::Paste(_) | Msg::Tick => Cmd::None,
+        };
+        (self, cmd)
+    }
+
+    fn view<B: Backend>(&self, _screen: &TestScreen, rect: &mut Frame<B>, area: Rect) {
+        let body = Paragraph::new(format!("Sleeps: {}", self.sleeps));
+        rect.render_widget(body, area);
+    }
+}
+
+/// Capacity of the IO requests of a test app
+pub const TEST_QUEUE: usize = 10;
+
+/// The IO requests dispatched by a test app
+pub type TestRequests = mpsc::Receiver<IoRequest<TestEvent>>;
+
+/// An app of the default test model and keys, with its IO requests
+pub fn test_app() -> (App<TestModel>, TestRequests) {
+    test_app_with(TestModel::default(), Keymap::default())
+}
+
+/// An app of the given test model and keys, with its IO requests
+pub fn test_app_with(
+    model: TestModel,
+    keymap: Keymap<TestAction>,
+) -> (App<TestModel>, TestRequests) {
+    let (io_tx, io_rx) = mpsc::channel(TEST_QUEUE);
+    (App::new(model, io_tx, keymap), io_rx)
+}
+
+/// Press the keys of a text one by one, `\n` is `Enter`
+pub async fn type_keys(app: &mut App<TestModel>, text: &str) {
+    for c in text.chars() {
+        let key = if c == '\n' { Key::Enter } else { Key::Char(c) };
+        app.do_action(key).await;
+    }
+}